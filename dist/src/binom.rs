@@ -0,0 +1,167 @@
+//!
+//! Mathlib : A C Library of Special Functions
+//! Copyright (C) 2000-2021 The R Core Team
+//! Copyright (C) 1998 Ross Ihaka
+//!
+//! This program is free software; you can redistribute it and/or modify
+//! it under the terms of the GNU General Public License as published by
+//! the Free Software Foundation; either version 2 of the License, or
+//! (at your option) any later version.
+//!
+//! This program is distributed in the hope that it will be useful,
+//! but WITHOUT ANY WARRANTY; without even the implied warranty of
+//! MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//! GNU General Public License for more details.
+//!
+//! You should have received a copy of the GNU General Public License
+//! along with this program; if not, a copy is available at
+//! https://www.R-project.org/Licenses/
+//!
+
+use crate::nmath;
+use crate::rmath;
+
+extern "C" {
+    #[link_name = "Rf_stirlerr"]
+    fn stirlerr(n: f64) -> f64;
+    #[link_name = "Rf_bd0"]
+    fn bd0(x: f64, np: f64) -> f64;
+    fn pbeta(x: f64, a: f64, b: f64, lower_tail: i32, log_p: i32) -> f64;
+}
+
+const M_LN_2PI: f64 = 1.837_877_066_409_345_5; /* log(2*pi) */
+
+/// Probability of `x` successes in `n` trials with success probability `p` and `q = 1 - p`.
+///
+/// Uses the saddle point expansion of Loader (2000), which stays accurate for large `n`.
+pub fn dbinom_raw(x: f64, n: f64, p: f64, q: f64) -> f64 {
+    if p == 0.0 {
+        return if x == 0.0 { 1.0 } else { 0.0 };
+    }
+    if q == 0.0 {
+        return if x == n { 1.0 } else { 0.0 };
+    }
+    if x == 0.0 {
+        if n == 0.0 {
+            return 1.0;
+        }
+        let lc = if p < 0.1 {
+            unsafe { -bd0(n, n * q) - n * p }
+        } else {
+            n * q.ln()
+        };
+        return lc.exp();
+    }
+    if x == n {
+        let lc = if q < 0.1 {
+            unsafe { -bd0(n, n * p) - n * q }
+        } else {
+            n * p.ln()
+        };
+        return lc.exp();
+    }
+    if x < 0.0 || x > n {
+        return 0.0;
+    }
+    let lc =
+        unsafe { stirlerr(n) - stirlerr(x) - stirlerr(n - x) - bd0(x, n * p) - bd0(n - x, n * q) };
+    let lf = M_LN_2PI + x.ln() + (-x / n).ln_1p();
+    (lc - 0.5 * lf).exp()
+}
+
+/// Probability of `x` successes in `n` trials with success probability `p`.
+pub fn dbinom(x: f64, n: f64, p: f64) -> f64 {
+    if !(0.0..=1.0).contains(&p) || n < 0.0 || n.fract() != 0.0 {
+        return nmath::ml_warn_return_nan();
+    }
+    if x < 0.0 || x.fract() != 0.0 || !x.is_finite() {
+        return 0.0;
+    }
+    dbinom_raw(x, n, p, 1.0 - p)
+}
+
+/// Probability of at most `x` successes, or of more than `x` if not `lower_tail`.
+pub fn pbinom(x: f64, n: f64, p: f64, lower_tail: bool) -> f64 {
+    if !(0.0..=1.0).contains(&p) || n < 0.0 || n.fract() != 0.0 {
+        return nmath::ml_warn_return_nan();
+    }
+    let x = (x + 1e-7).floor();
+    let (below, above) = if lower_tail { (0.0, 1.0) } else { (1.0, 0.0) };
+    if x < 0.0 {
+        return below;
+    }
+    if n <= x {
+        return above;
+    }
+    unsafe { pbeta(p, x + 1.0, n - x, !lower_tail as i32, 0) }
+}
+
+/// Smallest number of successes `x` for which `pbinom(x, n, pr, true)` is at least `p`, or,
+/// if not `lower_tail`, for which `pbinom(x, n, pr, false)` is at most `p`.
+pub fn qbinom(p: f64, n: f64, pr: f64, lower_tail: bool) -> f64 {
+    if !(0.0..=1.0).contains(&p) || !(0.0..=1.0).contains(&pr) || n < 0.0 || n.fract() != 0.0 {
+        return nmath::ml_warn_return_nan();
+    }
+    // Work with the lower tail, so find the smallest x with P(X <= x) >= p.
+    let p = if lower_tail { p } else { 0.5 - p + 0.5 };
+    if pr == 0.0 || n == 0.0 || p <= 0.0 {
+        return 0.0;
+    }
+    let q = 1.0 - pr;
+    // All the mass is at n if pr = 1.
+    if q == 0.0 || p >= 1.0 {
+        return n;
+    }
+    let mu = n * pr;
+    let sigma = (n * pr * q).sqrt();
+    let gamma = (q - pr) / sigma;
+
+    // Cornish-Fisher initial approximation, then a local search.
+    let z = rmath::qnorm(p, 0.0, 1.0, true, false);
+    let mut y = (mu + sigma * (z + gamma * (z * z - 1.0) / 6.0) + 0.5).floor();
+    y = y.clamp(0.0, n);
+
+    // Fuzz to ensure left continuity.
+    let p = p * (1.0 - 64.0 * f64::EPSILON);
+    if pbinom(y, n, pr, true) >= p {
+        while y > 0.0 && pbinom(y - 1.0, n, pr, true) >= p {
+            y -= 1.0;
+        }
+    } else {
+        while y < n && pbinom(y, n, pr, true) < p {
+            y += 1.0;
+        }
+    }
+    y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn matches_r() {
+        // dbinom(3, 10, 0.3), pbinom(3, 10, 0.3), and pbinom(3, 10, 0.3, lower.tail = FALSE).
+        assert_relative_eq!(dbinom(3.0, 10.0, 0.3), 0.266_827_932, epsilon = 1e-9);
+        assert_relative_eq!(
+            pbinom(3.0, 10.0, 0.3, true),
+            0.649_610_718_4,
+            epsilon = 1e-9
+        );
+        assert_relative_eq!(
+            pbinom(3.0, 10.0, 0.3, false),
+            0.350_389_281_6,
+            epsilon = 1e-9
+        );
+        assert_eq!(pbinom(-1.0, 10.0, 0.3, true), 0.0);
+        assert_eq!(pbinom(10.0, 10.0, 0.3, true), 1.0);
+        // qbinom(0.5, 10, 0.3) and qbinom(0.05, 100, 0.5, lower.tail = FALSE).
+        assert_eq!(qbinom(0.5, 10.0, 0.3, true), 3.0);
+        assert_eq!(qbinom(0.05, 100.0, 0.5, false), 58.0);
+        // qbinom(0.5, 10, 1).
+        assert_eq!(qbinom(0.5, 10.0, 1.0, true), 10.0);
+        let total: f64 = (0..=40).map(|x| dbinom(x as f64, 40.0, 0.7)).sum();
+        assert_relative_eq!(total, 1.0, epsilon = 1e-12);
+    }
+}
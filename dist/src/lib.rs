@@ -1,7 +1,10 @@
+mod binom;
+mod corr;
 mod dpq;
 mod nmath;
 mod pnt;
-mod rmath;
+pub mod rmath;
+pub mod tost;
 
 extern "C" {
     fn qnt(p: f64, df: f64, ncp: f64, lower_tail: i32, log_p: i32) -> f64;
@@ -17,6 +20,10 @@ pub trait Distribution {
     fn cdf(&self, x: f64, lower_tail: bool) -> f64;
     fn quantile(&self, x: f64, lower_tail: bool) -> f64;
     fn central_distribution(&self) -> Box<dyn Distribution>;
+    /// Noncentrality parameter.
+    fn ncp(&self) -> f64;
+    /// Degrees of freedom; the F-distribution has two.
+    fn df(&self) -> Vec<f64>;
 }
 
 pub type Dist = Box<dyn Distribution>;
@@ -48,6 +55,12 @@ impl Distribution for NoncentralT {
         clone.lambda = 0.0;
         Box::new(clone)
     }
+    fn ncp(&self) -> f64 {
+        self.lambda
+    }
+    fn df(&self) -> Vec<f64> {
+        vec![self.v]
+    }
 }
 
 /// Implements the noncentral F-distribution with `v1` and `v2` degrees of freedom and
@@ -95,6 +108,12 @@ impl Distribution for NoncentralF {
         clone.lambda = 0.0;
         Box::new(clone)
     }
+    fn ncp(&self) -> f64 {
+        self.lambda
+    }
+    fn df(&self) -> Vec<f64> {
+        vec![self.v1, self.v2]
+    }
 }
 
 /// Implements the noncentral Chi-squared distribution with `v` degrees of freedom and
@@ -127,6 +146,147 @@ impl Distribution for NoncentralChisq {
         clone.lambda = 0.0;
         Box::new(clone)
     }
+    fn ncp(&self) -> f64 {
+        self.lambda
+    }
+    fn df(&self) -> Vec<f64> {
+        vec![self.v]
+    }
+}
+
+/// Implements the normal distribution with mean `mu` and standard deviation `sigma`.
+///
+/// The central distribution is the standard normal, so `mu` plays the role of the
+/// noncentrality parameter of a z statistic.
+#[derive(Clone)]
+pub struct Normal {
+    mu: f64,
+    sigma: f64,
+}
+
+impl Normal {
+    pub fn new(mu: f64, sigma: f64) -> Self {
+        Self { mu, sigma }
+    }
+}
+
+impl Distribution for Normal {
+    fn cdf(&self, x: f64, lower_tail: bool) -> f64 {
+        rmath::pnorm(x, self.mu, self.sigma, lower_tail, false)
+    }
+    fn quantile(&self, x: f64, lower_tail: bool) -> f64 {
+        rmath::qnorm(x, self.mu, self.sigma, lower_tail, false)
+    }
+    fn central_distribution(&self) -> Dist {
+        Box::new(Normal::new(0.0, 1.0))
+    }
+    fn ncp(&self) -> f64 {
+        self.mu
+    }
+    fn df(&self) -> Vec<f64> {
+        vec![]
+    }
+}
+
+/// Implements the distribution of the sample correlation coefficient of `n` pairs from a
+/// bivariate normal distribution with correlation `rho`.
+///
+/// The central distribution is the one for `rho = 0`, so `rho` plays the role of the
+/// noncentrality parameter.
+#[derive(Clone)]
+pub struct SampleCorrelation {
+    n: f64,
+    rho: f64,
+}
+
+impl SampleCorrelation {
+    pub fn new(n: f64, rho: f64) -> Self {
+        Self { n, rho }
+    }
+}
+
+impl Distribution for SampleCorrelation {
+    fn cdf(&self, x: f64, lower_tail: bool) -> f64 {
+        corr::pcorr(x, self.n, self.rho, lower_tail)
+    }
+    fn quantile(&self, x: f64, lower_tail: bool) -> f64 {
+        corr::qcorr(x, self.n, self.rho, lower_tail)
+    }
+    fn central_distribution(&self) -> Dist {
+        let mut clone = self.clone();
+        clone.rho = 0.0;
+        Box::new(clone)
+    }
+    fn ncp(&self) -> f64 {
+        self.rho
+    }
+    fn df(&self) -> Vec<f64> {
+        vec![self.n - 2.0]
+    }
+}
+
+/// Implements the distribution of `scale` times a variable with distribution `inner`.
+///
+/// The central distribution is the unscaled central version of `inner`, so `scale` plays
+/// the role of the noncentrality parameter, as for the ratio of the true to the
+/// hypothesized variance.
+pub struct Scaled {
+    inner: Dist,
+    scale: f64,
+}
+
+impl Scaled {
+    pub fn new(inner: Dist, scale: f64) -> Self {
+        Self { inner, scale }
+    }
+}
+
+impl Distribution for Scaled {
+    fn cdf(&self, x: f64, lower_tail: bool) -> f64 {
+        self.inner.cdf(x / self.scale, lower_tail)
+    }
+    fn quantile(&self, x: f64, lower_tail: bool) -> f64 {
+        self.scale * self.inner.quantile(x, lower_tail)
+    }
+    fn central_distribution(&self) -> Dist {
+        Box::new(Scaled::new(self.inner.central_distribution(), 1.0))
+    }
+    fn ncp(&self) -> f64 {
+        self.scale
+    }
+    fn df(&self) -> Vec<f64> {
+        self.inner.df()
+    }
+}
+
+/// Implements the binomial distribution of the number of successes in `n` trials with
+/// success probability `p`.
+///
+/// This is a discrete distribution, so it is not a `Distribution`: there is no noncentral
+/// version and `quantile` returns a number of successes.
+#[derive(Clone)]
+pub struct Binomial {
+    n: f64,
+    p: f64,
+}
+
+impl Binomial {
+    pub fn new(n: f64, p: f64) -> Self {
+        Self { n, p }
+    }
+    /// Probability of exactly `x` successes.
+    pub fn pmf(&self, x: f64) -> f64 {
+        binom::dbinom(x, self.n, self.p)
+    }
+    /// Probability of at most `x` successes, or of more than `x` if not `lower_tail`.
+    pub fn cdf(&self, x: f64, lower_tail: bool) -> f64 {
+        binom::pbinom(x, self.n, self.p, lower_tail)
+    }
+    /// Smallest number of successes `x` with `cdf(x, lower_tail)` at least `p` or, if not
+    /// `lower_tail`, at most `p`.
+    pub fn quantile(&self, p: f64, lower_tail: bool) -> f64 {
+        binom::qbinom(p, self.n, self.p, lower_tail)
+    }
 }
 
 #[cfg(test)]
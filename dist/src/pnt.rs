@@ -31,9 +31,6 @@ extern "C" {
 fn finis(mut tnc: f64, del: f64, negdel: bool, mut lower_tail: bool) -> f64 {
     tnc += rmath::pnorm(-del, 0.0, 1.0, /*lower*/ true, /*log_p*/ false);
     lower_tail = lower_tail != negdel;
-    if tnc > 1.0 - 1e-10 && lower_tail {
-        println!("precision problem in pnt");
-    }
     dpq::r_dt_val(f64::min(tnc, 1.0), lower_tail, /*log_p*/ false)
 }
 
@@ -118,7 +115,6 @@ pub fn pnt(t: f64, df: f64, ncp: f64, lower_tail: bool, log_p: bool) -> f64 {
         lambda = del * del;
         p = 0.5 * (-0.5 * lambda).exp();
         if p == 0.0 {
-            println!("Underflow in pnt; |ncp| too large");
             return dpq::r_dt_0(lower_tail, log_p);
         }
         q = rmath::M_SQRT_2dPI * p * del;
@@ -156,14 +152,12 @@ pub fn pnt(t: f64, df: f64, ncp: f64, lower_tail: bool, log_p: bool) -> f64 {
             tnc += p * xodd + q * xeven;
             s -= p;
             if s < -1e-10 {
-                println!("precision problem in pnt");
                 return finis(tnc, del, negdel, lower_tail);
             }
             errbd = 2. * s * (xodd - godd);
             if errbd.abs() < errmax {
                 return finis(tnc, del, negdel, lower_tail);
             }
-            println!("pnt didn't converge");
         }
     } else {
         tnc = 0.0;
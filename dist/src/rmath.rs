@@ -6,8 +6,13 @@ pub const M_LN_SQRT_PI: f64 = 0.572_364_942_924_700_1; /* log(sqrt(pi)) */
 
 extern "C" {
     fn pnorm5(x: f64, mu: f64, sigma: f64, lower_tail: i32, log_p: i32) -> f64;
+    fn qnorm5(p: f64, mu: f64, sigma: f64, lower_tail: i32, log_p: i32) -> f64;
 }
 
 pub fn pnorm(x: f64, mu: f64, sigma: f64, lower_tail: bool, log_p: bool) -> f64 {
     unsafe { pnorm5(x, mu, sigma, lower_tail as i32, log_p as i32) }
 }
+
+pub fn qnorm(p: f64, mu: f64, sigma: f64, lower_tail: bool, log_p: bool) -> f64 {
+    unsafe { qnorm5(p, mu, sigma, lower_tail as i32, log_p as i32) }
+}
@@ -0,0 +1,78 @@
+//!
+//! Joint distribution of the two t statistics of the two one-sided tests (TOST) procedure.
+//!
+//! Both statistics share the estimated standard deviation, so given it, rejecting both null
+//! hypotheses means that the normally distributed mean difference lies in an interval. The
+//! probability is the integral over the chi-distributed standard deviation (Owen, 1965).
+//!
+
+use crate::rmath;
+
+extern "C" {
+    fn lgammafn(x: f64) -> f64;
+}
+
+/// Number of Simpson intervals of the integral over the standard deviation.
+const INTERVALS: usize = 320;
+
+/// Half width of the integration range around the mean of the chi-distributed variable,
+/// whose standard deviation is below 1/sqrt(2).
+const HALF_WIDTH: f64 = 8.0;
+
+/// Probability that T1 = (Z + `ncp_lower`) / W is at least `critical` and that
+/// T2 = (Z + `ncp_upper`) / W is at most `-critical`, where Z is standard normal and W is
+/// the square root of a chi-squared variable with `df` degrees of freedom divided by `df`.
+pub fn ptost(critical: f64, df: f64, ncp_lower: f64, ncp_upper: f64) -> f64 {
+    let start = (df.sqrt() - HALF_WIDTH).max(0.0);
+    let mut end = df.sqrt() + HALF_WIDTH;
+    if critical > 0.0 {
+        // Given s = sqrt(df)·W, the interval of Z is empty from s = last onwards.
+        end = end.min(df.sqrt() * (ncp_lower - ncp_upper) / (2.0 * critical));
+    }
+    if end <= start {
+        return 0.0;
+    }
+    let log_constant = (1.0 - df / 2.0) * std::f64::consts::LN_2 - unsafe { lgammafn(df / 2.0) };
+    let h = (end - start) / INTERVALS as f64;
+    let mut total = 0.0;
+    for i in 0..=INTERVALS {
+        let s = start + i as f64 * h;
+        let simpson = match i {
+            0 => 1.0,
+            i if i == INTERVALS => 1.0,
+            i if i % 2 == 1 => 4.0,
+            _ => 2.0,
+        };
+        let density = if s == 0.0 {
+            if df == 1.0 {
+                log_constant.exp()
+            } else {
+                0.0
+            }
+        } else {
+            (log_constant + (df - 1.0) * s.ln() - s * s / 2.0).exp()
+        };
+        let w = s / df.sqrt();
+        let upper = rmath::pnorm(-critical * w - ncp_upper, 0.0, 1.0, true, false);
+        let lower = rmath::pnorm(critical * w - ncp_lower, 0.0, 1.0, true, false);
+        total += simpson * density * (upper - lower).max(0.0);
+    }
+    total * h / 3.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn matches_integral() {
+        // Integrals over the chi density, computed with mpmath.
+        assert_relative_eq!(ptost(1.7, 20.0, 3.0, -3.0), 0.798_073_118, epsilon = 1e-7);
+        assert_relative_eq!(ptost(1.697, 30.0, 2.5, -1.8), 0.334_538_020, epsilon = 1e-7);
+        assert_relative_eq!(ptost(2.0, 5.0, 4.0, -4.0), 0.923_142_096, epsilon = 1e-7);
+        assert_relative_eq!(ptost(1.65, 200.0, 3.0, -2.5), 0.713_197_747, epsilon = 1e-7);
+        // The interval is empty for every standard deviation if the margins overlap.
+        assert_eq!(ptost(1.7, 20.0, 1.0, 1.5), 0.0);
+    }
+}
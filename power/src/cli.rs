@@ -0,0 +1,736 @@
+#![allow(clippy::upper_case_acronyms)]
+///
+/// Command-line interface for the `pa` binary.
+///
+use crate::error::PowerError;
+use crate::interface::Request;
+use crate::interface::Response;
+use clap::Args;
+use clap::Parser;
+use clap::Subcommand;
+use clap::ValueEnum;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+
+#[derive(Parser)]
+#[command(name = "pa", about = "Statistical power analyses")]
+pub struct Cli {
+    #[command(subcommand)]
+    analysis: Command,
+    /// Print the result as JSON.
+    #[arg(long, global = true)]
+    json: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Compute required sample size (a priori).
+    N {
+        #[command(flatten)]
+        design: Design,
+        #[arg(long)]
+        alpha: f64,
+        #[arg(long)]
+        power: f64,
+        /// Effect size; not needed if the test derives it from other parameters.
+        #[arg(long)]
+        es: Option<f64>,
+    },
+    /// Compute required α (criterion).
+    Alpha {
+        #[command(flatten)]
+        design: Design,
+        #[arg(long)]
+        n: f64,
+        #[arg(long)]
+        power: f64,
+        /// Effect size; not needed if the test derives it from other parameters.
+        #[arg(long)]
+        es: Option<f64>,
+    },
+    /// Compute achieved power (post hoc).
+    Power {
+        #[command(flatten)]
+        design: Design,
+        #[arg(long)]
+        n: f64,
+        #[arg(long)]
+        alpha: f64,
+        /// Effect size; not needed if the test derives it from other parameters.
+        #[arg(long)]
+        es: Option<f64>,
+    },
+    /// Compute required effect size (sensitivity).
+    Es {
+        #[command(flatten)]
+        design: Design,
+        #[arg(long)]
+        n: f64,
+        #[arg(long)]
+        alpha: f64,
+        #[arg(long)]
+        power: f64,
+        /// Side of the null value to search for the effect size.
+        #[arg(long, value_enum, default_value = "greater")]
+        direction: DirectionName,
+    },
+    /// Compute implied α and power from the ratio β/α (compromise).
+    Compromise {
+        #[command(flatten)]
+        design: Design,
+        #[arg(long)]
+        n: f64,
+        /// Effect size; not needed if the test derives it from other parameters.
+        #[arg(long)]
+        es: Option<f64>,
+        /// Ratio β/α of the error probabilities.
+        #[arg(long = "betaAlphaRatio", alias = "beta-alpha-ratio")]
+        beta_alpha_ratio: f64,
+    },
+}
+
+/// Names match the `test` field of the JSON interface.
+#[derive(Clone, Copy, ValueEnum)]
+enum TestName {
+    #[value(name = "oneSampleTTest")]
+    OneSampleTTest,
+    #[value(name = "independentSamplesTTest")]
+    IndependentSamplesTTest,
+    #[value(name = "independentSamplesWelchTTest")]
+    IndependentSamplesWelchTTest,
+    #[value(name = "pairedSamplesTTest")]
+    PairedSamplesTTest,
+    #[value(name = "oneSampleEquivalenceTest")]
+    OneSampleEquivalenceTest,
+    #[value(name = "pairedSamplesEquivalenceTest")]
+    PairedSamplesEquivalenceTest,
+    #[value(name = "independentSamplesEquivalenceTest")]
+    IndependentSamplesEquivalenceTest,
+    #[value(name = "wilcoxonSignedRankTest")]
+    WilcoxonSignedRankTest,
+    #[value(name = "wilcoxonSignedRankPairedTest")]
+    WilcoxonSignedRankPairedTest,
+    #[value(name = "mannWhitneyTest")]
+    MannWhitneyTest,
+    #[value(name = "varianceTest")]
+    VarianceTest,
+    #[value(name = "twoVariancesTest")]
+    TwoVariancesTest,
+    #[value(name = "exactBinomialTest")]
+    ExactBinomialTest,
+    #[value(name = "twoProportionsTest")]
+    TwoProportionsTest,
+    #[value(name = "mcNemarTest")]
+    McNemarTest,
+    #[value(name = "correlationTest")]
+    CorrelationTest,
+    #[value(name = "twoCorrelationsTest")]
+    TwoCorrelationsTest,
+    #[value(name = "dependentCorrelationsCommonIndexTest")]
+    DependentCorrelationsCommonIndexTest,
+    #[value(name = "dependentCorrelationsNoCommonIndexTest")]
+    DependentCorrelationsNoCommonIndexTest,
+    #[value(name = "survivalTest")]
+    SurvivalTest,
+    #[value(name = "goodnessOfFitChisqTest")]
+    GoodnessOfFitChisqTest,
+    #[value(name = "deviationFromZeroMultipleRegression")]
+    DeviationFromZeroMultipleRegression,
+    #[value(name = "increaseMultipleRegression")]
+    IncreaseMultipleRegression,
+    #[value(name = "logisticRegression")]
+    LogisticRegression,
+    #[value(name = "poissonRegression")]
+    PoissonRegression,
+    #[value(name = "ANCOVA")]
+    ANCOVA,
+    #[value(name = "oneWayANOVA")]
+    OneWayANOVA,
+    #[value(name = "twoWayANOVA")]
+    TwoWayANOVA,
+    #[value(name = "oneSampleHotellingT2Test")]
+    OneSampleHotellingT2Test,
+    #[value(name = "twoSampleHotellingT2Test")]
+    TwoSampleHotellingT2Test,
+    #[value(name = "globalMANOVA")]
+    GlobalMANOVA,
+    #[value(name = "betweenRepeatedANOVA")]
+    BetweenRepeatedANOVA,
+    #[value(name = "withinRepeatedANOVA")]
+    WithinRepeatedANOVA,
+    #[value(name = "withinBetweenRepeatedANOVA")]
+    WithinBetweenRepeatedANOVA,
+    #[value(name = "betweenRepeatedMANOVA")]
+    BetweenRepeatedMANOVA,
+    #[value(name = "withinRepeatedMANOVA")]
+    WithinRepeatedMANOVA,
+    #[value(name = "withinBetweenRepeatedMANOVA")]
+    WithinBetweenRepeatedMANOVA,
+    #[value(name = "genericT")]
+    GenericT,
+    #[value(name = "genericF")]
+    GenericF,
+    #[value(name = "genericChisq")]
+    GenericChisq,
+    #[value(name = "genericZ")]
+    GenericZ,
+}
+
+/// Names match the `method` field of the JSON interface.
+#[derive(Clone, Copy, ValueEnum)]
+enum MethodName {
+    #[value(name = "zTest")]
+    ZTest,
+    #[value(name = "zTestCorrected")]
+    ZTestCorrected,
+    #[value(name = "fisher")]
+    Fisher,
+    #[value(name = "arcsine")]
+    Arcsine,
+    #[value(name = "exact")]
+    Exact,
+    #[value(name = "asymptotic")]
+    Asymptotic,
+    #[value(name = "fisherZ")]
+    FisherZ,
+    #[value(name = "demidenko")]
+    Demidenko,
+    #[value(name = "hsieh")]
+    Hsieh,
+    #[value(name = "schoenfeld")]
+    Schoenfeld,
+    #[value(name = "freedman")]
+    Freedman,
+}
+
+/// Names match the `statistic` field of the JSON interface.
+#[derive(Clone, Copy, ValueEnum)]
+enum StatisticName {
+    #[value(name = "pillaiV")]
+    PillaiV,
+    #[value(name = "wilksLambda")]
+    WilksLambda,
+    #[value(name = "hotellingLawley")]
+    HotellingLawley,
+}
+
+/// Names match the `parent` field of the JSON interface.
+#[derive(Clone, Copy, ValueEnum)]
+enum ParentName {
+    #[value(name = "normal")]
+    Normal,
+    #[value(name = "laplace")]
+    Laplace,
+    #[value(name = "logistic")]
+    Logistic,
+    #[value(name = "minARE")]
+    MinARE,
+}
+
+/// Names match the `mode` field of the JSON interface.
+#[derive(Clone, Copy, ValueEnum)]
+enum ModeName {
+    #[value(name = "equivalence")]
+    Equivalence,
+    #[value(name = "nonInferiority")]
+    NonInferiority,
+}
+
+/// Names match the `xDistribution` field of the JSON interface.
+#[derive(Clone, Copy, ValueEnum)]
+enum DistributionName {
+    #[value(name = "normal")]
+    Normal,
+    #[value(name = "binomial")]
+    Binomial,
+}
+
+/// Names match the `direction` field of the JSON interface.
+#[derive(Clone, Copy, ValueEnum)]
+enum DirectionName {
+    #[value(name = "greater")]
+    Greater,
+    #[value(name = "less")]
+    Less,
+}
+
+#[derive(Args)]
+struct Design {
+    /// Statistical test.
+    #[arg(long, value_enum)]
+    test: TestName,
+    /// Number of tails (1 or 2).
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+    tail: u8,
+    /// Number of groups.
+    #[arg(long)]
+    k: Option<i64>,
+    /// Levels of repeated measures.
+    #[arg(long)]
+    m: Option<i64>,
+    /// Correlation among repeated measures.
+    #[arg(long)]
+    rho: Option<f64>,
+    /// Nonsphericity correction.
+    #[arg(long)]
+    epsilon: Option<f64>,
+    /// Number of predictors or covariates.
+    #[arg(long)]
+    p: Option<i64>,
+    /// Number of tested predictors or degrees of freedom of the tested effect.
+    #[arg(long)]
+    q: Option<i64>,
+    /// Degrees of freedom; an expression in n and es for generic tests.
+    #[arg(long)]
+    df: Option<String>,
+    /// Numerator degrees of freedom of a generic F test, as an expression in n and es.
+    #[arg(long)]
+    df1: Option<String>,
+    /// Denominator degrees of freedom of a generic F test, as an expression in n and es.
+    #[arg(long)]
+    df2: Option<String>,
+    /// Noncentrality parameter of a generic test, as an expression in n and es; defaults to
+    /// es.
+    #[arg(long)]
+    ncp: Option<String>,
+    /// Number of predictors.
+    #[arg(long = "nPredictors", alias = "n-predictors")]
+    n_predictors: Option<i64>,
+    /// Number of response variables.
+    #[arg(long = "nResponses", alias = "n-responses")]
+    n_responses: Option<i64>,
+    /// Multivariate test statistic; defaults to pillaiV.
+    #[arg(long, value_enum)]
+    statistic: Option<StatisticName>,
+    /// Mean of the first measurement or group.
+    #[arg(long)]
+    mean1: Option<f64>,
+    /// Mean of the second measurement or group.
+    #[arg(long)]
+    mean2: Option<f64>,
+    /// Standard deviation of the first measurement or group.
+    #[arg(long)]
+    sd1: Option<f64>,
+    /// Standard deviation of the second measurement or group.
+    #[arg(long)]
+    sd2: Option<f64>,
+    /// Allocation ratio N2/N1 of two groups; defaults to 1.
+    #[arg(long = "allocRatio", alias = "alloc-ratio")]
+    alloc_ratio: Option<f64>,
+    /// Size of the first group.
+    #[arg(long)]
+    n1: Option<f64>,
+    /// Size of the second group.
+    #[arg(long)]
+    n2: Option<f64>,
+    /// Proportion under the null hypothesis.
+    #[arg(long)]
+    p0: Option<f64>,
+    /// Proportion in the first group.
+    #[arg(long)]
+    p1: Option<f64>,
+    /// Proportion in the second group.
+    #[arg(long)]
+    p2: Option<f64>,
+    /// How power is computed; defaults to zTest for two proportions and to exact otherwise.
+    #[arg(long, value_enum)]
+    method: Option<MethodName>,
+    /// Proportion of discordant pairs.
+    #[arg(long = "propDiscordant", alias = "prop-discordant")]
+    prop_discordant: Option<f64>,
+    /// Proportion of pairs with a success in the first measurement only.
+    #[arg(long)]
+    p12: Option<f64>,
+    /// Proportion of pairs with a success in the second measurement only.
+    #[arg(long)]
+    p21: Option<f64>,
+    /// Correlation under the null hypothesis.
+    #[arg(long)]
+    rho0: Option<f64>,
+    /// Correlation in the first group.
+    #[arg(long)]
+    rho1: Option<f64>,
+    /// Correlation in the second group.
+    #[arg(long)]
+    rho2: Option<f64>,
+    /// Correlation between variables 1 and 2 of dependent correlations.
+    #[arg(long)]
+    rho12: Option<f64>,
+    /// Correlation between variables 1 and 3 of dependent correlations.
+    #[arg(long)]
+    rho13: Option<f64>,
+    /// Correlation between variables 1 and 4 of dependent correlations.
+    #[arg(long)]
+    rho14: Option<f64>,
+    /// Correlation between variables 2 and 3 of dependent correlations.
+    #[arg(long)]
+    rho23: Option<f64>,
+    /// Correlation between variables 2 and 4 of dependent correlations.
+    #[arg(long)]
+    rho24: Option<f64>,
+    /// Correlation between variables 3 and 4 of dependent correlations.
+    #[arg(long)]
+    rho34: Option<f64>,
+    /// Squared multiple correlation of the tested predictor with the other predictors;
+    /// defaults to 0.
+    #[arg(long = "r2Other", alias = "r2-other")]
+    r2_other: Option<f64>,
+    /// Distribution of the tested predictor.
+    #[arg(long = "xDistribution", alias = "x-distribution", value_enum)]
+    x_distribution: Option<DistributionName>,
+    /// Mean of a normal predictor.
+    #[arg(long = "xMean", alias = "x-mean")]
+    x_mean: Option<f64>,
+    /// Standard deviation of a normal predictor.
+    #[arg(long = "xSd", alias = "x-sd")]
+    x_sd: Option<f64>,
+    /// Proportion of ones of a binary predictor.
+    #[arg(long = "xProp", alias = "x-prop")]
+    x_prop: Option<f64>,
+    /// Event rate per unit of exposure at x = 0 of a Poisson regression.
+    #[arg(long = "baseRate", alias = "base-rate")]
+    base_rate: Option<f64>,
+    /// Mean exposure time of a Poisson regression.
+    #[arg(long)]
+    exposure: Option<f64>,
+    /// Median survival time in the first group.
+    #[arg(long)]
+    median1: Option<f64>,
+    /// Length of the accrual period.
+    #[arg(long)]
+    accrual: Option<f64>,
+    /// Follow-up time after the end of accrual.
+    #[arg(long = "followUp", alias = "follow-up")]
+    follow_up: Option<f64>,
+    /// Probability of an event in the first group.
+    #[arg(long = "eventProb1", alias = "event-prob1")]
+    event_prob1: Option<f64>,
+    /// Probability of an event in the second group.
+    #[arg(long = "eventProb2", alias = "event-prob2")]
+    event_prob2: Option<f64>,
+    /// Lower equivalence margin, or the non-inferiority margin.
+    #[arg(long)]
+    lower: Option<f64>,
+    /// Upper equivalence margin.
+    #[arg(long)]
+    upper: Option<f64>,
+    /// Hypotheses of an equivalence test; defaults to equivalence.
+    #[arg(long, value_enum)]
+    mode: Option<ModeName>,
+    /// Parent distribution of a rank test; defaults to normal.
+    #[arg(long, value_enum)]
+    parent: Option<ParentName>,
+    /// Variance ratio σ2²/σ1² of two groups.
+    #[arg(long = "varRatio", alias = "var-ratio")]
+    var_ratio: Option<f64>,
+}
+
+/// Returns the name of `value` in the JSON interface.
+fn name<T: ValueEnum>(value: Option<T>) -> Option<Value> {
+    let value = value?.to_possible_value()?;
+    Some(Value::from(value.get_name()))
+}
+
+impl Design {
+    /// Returns the given design parameters under their names in the JSON interface.
+    fn parameters(&self) -> Map<String, Value> {
+        let parameters = [
+            ("test", name(Some(self.test))),
+            ("tail", Some(Value::from(self.tail))),
+            ("k", self.k.map(Value::from)),
+            ("m", self.m.map(Value::from)),
+            ("rho", self.rho.map(Value::from)),
+            ("epsilon", self.epsilon.map(Value::from)),
+            ("p", self.p.map(Value::from)),
+            ("q", self.q.map(Value::from)),
+            ("df", self.df.clone().map(Value::from)),
+            ("df1", self.df1.clone().map(Value::from)),
+            ("df2", self.df2.clone().map(Value::from)),
+            ("ncp", self.ncp.clone().map(Value::from)),
+            ("nPredictors", self.n_predictors.map(Value::from)),
+            ("nResponses", self.n_responses.map(Value::from)),
+            ("statistic", name(self.statistic)),
+            ("mean1", self.mean1.map(Value::from)),
+            ("mean2", self.mean2.map(Value::from)),
+            ("sd1", self.sd1.map(Value::from)),
+            ("sd2", self.sd2.map(Value::from)),
+            ("allocRatio", self.alloc_ratio.map(Value::from)),
+            ("n1", self.n1.map(Value::from)),
+            ("n2", self.n2.map(Value::from)),
+            ("p0", self.p0.map(Value::from)),
+            ("p1", self.p1.map(Value::from)),
+            ("p2", self.p2.map(Value::from)),
+            ("method", name(self.method)),
+            ("propDiscordant", self.prop_discordant.map(Value::from)),
+            ("p12", self.p12.map(Value::from)),
+            ("p21", self.p21.map(Value::from)),
+            ("rho0", self.rho0.map(Value::from)),
+            ("rho1", self.rho1.map(Value::from)),
+            ("rho2", self.rho2.map(Value::from)),
+            ("rho12", self.rho12.map(Value::from)),
+            ("rho13", self.rho13.map(Value::from)),
+            ("rho14", self.rho14.map(Value::from)),
+            ("rho23", self.rho23.map(Value::from)),
+            ("rho24", self.rho24.map(Value::from)),
+            ("rho34", self.rho34.map(Value::from)),
+            ("r2Other", self.r2_other.map(Value::from)),
+            ("xDistribution", name(self.x_distribution)),
+            ("xMean", self.x_mean.map(Value::from)),
+            ("xSd", self.x_sd.map(Value::from)),
+            ("xProp", self.x_prop.map(Value::from)),
+            ("baseRate", self.base_rate.map(Value::from)),
+            ("exposure", self.exposure.map(Value::from)),
+            ("median1", self.median1.map(Value::from)),
+            ("accrual", self.accrual.map(Value::from)),
+            ("followUp", self.follow_up.map(Value::from)),
+            ("eventProb1", self.event_prob1.map(Value::from)),
+            ("eventProb2", self.event_prob2.map(Value::from)),
+            ("lower", self.lower.map(Value::from)),
+            ("upper", self.upper.map(Value::from)),
+            ("mode", name(self.mode)),
+            ("parent", name(self.parent)),
+            ("varRatio", self.var_ratio.map(Value::from)),
+        ];
+        parameters
+            .into_iter()
+            .filter_map(|(key, value)| Some((key.to_string(), value?)))
+            .collect()
+    }
+}
+
+/// Builds the request that the wasm interface would receive for this command, so that
+/// parameters which do not apply to the test are rejected in the same way.
+fn request(command: &Command) -> Result<Request, PowerError> {
+    let (design, analysis) = match command {
+        Command::N {
+            design,
+            alpha,
+            power,
+            es,
+        } => (
+            design,
+            json!({"analysis": "n", "n": 0, "alpha": alpha, "power": power, "es": es}),
+        ),
+        Command::Alpha {
+            design,
+            n,
+            power,
+            es,
+        } => (
+            design,
+            json!({"analysis": "alpha", "n": n, "alpha": 0, "power": power, "es": es}),
+        ),
+        Command::Power {
+            design,
+            n,
+            alpha,
+            es,
+        } => (
+            design,
+            json!({"analysis": "power", "n": n, "alpha": alpha, "power": 0, "es": es}),
+        ),
+        Command::Es {
+            design,
+            n,
+            alpha,
+            power,
+            direction,
+        } => (
+            design,
+            json!({
+                "analysis": "es",
+                "n": n,
+                "alpha": alpha,
+                "power": power,
+                "direction": name(Some(*direction)),
+            }),
+        ),
+        Command::Compromise {
+            design,
+            n,
+            es,
+            beta_alpha_ratio,
+        } => (
+            design,
+            json!({
+                "analysis": "compromise",
+                "n": n,
+                "alpha": 0,
+                "power": 0,
+                "es": es,
+                "betaAlphaRatio": beta_alpha_ratio,
+            }),
+        ),
+    };
+    let mut data = design.parameters();
+    if let Value::Object(analysis) = analysis {
+        data.extend(analysis);
+    }
+    Request::from_value(Value::Object(data))
+}
+
+fn format_human(response: &Response) -> String {
+    let mut lines = vec![];
+    if let Some(n) = response.n {
+        lines.push(format!("n: {n}"));
+    }
+    if let (Some(n1), Some(n2)) = (response.n1, response.n2) {
+        lines.push(format!("group sizes: {n1}, {n2}"));
+    }
+    if let Some(actual_power) = response.actual_power {
+        lines.push(format!("actual power: {actual_power}"));
+    }
+    if let Some(alpha) = response.alpha {
+        lines.push(format!("alpha: {alpha}"));
+    }
+    if let Some(power) = response.power {
+        lines.push(format!("power: {power}"));
+    }
+    if let Some(es) = response.es {
+        lines.push(format!("es: {es}"));
+    }
+    let df: Vec<String> = response.df.iter().map(|df| df.to_string()).collect();
+    if let Some(ncp) = response.ncp {
+        lines.push(format!("noncentrality parameter: {ncp}"));
+    }
+    if !df.is_empty() {
+        lines.push(format!("degrees of freedom: {}", df.join(", ")));
+    }
+    if let Some(critical_value) = response.critical_value {
+        lines.push(format!("critical value: {critical_value}"));
+    }
+    if let Some(actual_alpha) = response.actual_alpha {
+        lines.push(format!("actual alpha: {actual_alpha}"));
+    }
+    if let Some(discordant_pairs) = response.discordant_pairs {
+        lines.push(format!("discordant pairs: {discordant_pairs}"));
+    }
+    if let Some(events) = response.events {
+        lines.push(format!("events: {events}"));
+    }
+    lines.join("\n")
+}
+
+pub fn run(cli: &Cli) -> Result<String, PowerError> {
+    let response = request(&cli.analysis)?.calculate()?;
+    if cli.json {
+        Ok(serde_json::to_string(&response).expect("response should serialize"))
+    } else {
+        Ok(format_human(&response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_args(args: &[&str]) -> Result<String, PowerError> {
+        let cli = Cli::try_parse_from(args).unwrap();
+        run(&cli)
+    }
+
+    #[test]
+    fn sample_size() {
+        let args = [
+            "pa",
+            "n",
+            "--test",
+            "oneSampleTTest",
+            "--tail",
+            "2",
+            "--alpha",
+            "0.05",
+            "--power",
+            "0.95",
+            "--es",
+            "0.5",
+        ];
+        let expected = [
+            "n: 54",
+            "actual power: 0.95",
+            "noncentrality parameter: 3.674",
+            "degrees of freedom: 53",
+            "critical value: 2.006",
+        ];
+        assert_eq!(run_args(&args).unwrap(), expected.join("\n"));
+    }
+
+    #[test]
+    fn json_output() {
+        let args = [
+            "pa",
+            "power",
+            "--test",
+            "oneWayANOVA",
+            "--k",
+            "5",
+            "--n",
+            "50",
+            "--alpha",
+            "0.05",
+            "--es",
+            "0.5",
+            "--json",
+        ];
+        let expected = r#"{"power":0.773,"ncp":12.5,"df":[4.0,45.0],"criticalValue":2.579}"#;
+        assert_eq!(run_args(&args).unwrap(), expected);
+    }
+
+    #[test]
+    fn missing_design_parameter() {
+        let args = [
+            "pa",
+            "power",
+            "--test",
+            "oneWayANOVA",
+            "--n",
+            "50",
+            "--alpha",
+            "0.05",
+            "--es",
+            "0.5",
+        ];
+        assert_eq!(run_args(&args).unwrap_err(), PowerError::missing_field("k"));
+    }
+
+    #[test]
+    fn parameter_that_does_not_apply() {
+        let args = [
+            "pa",
+            "power",
+            "--test",
+            "oneSampleTTest",
+            "--k",
+            "5",
+            "--n",
+            "50",
+            "--alpha",
+            "0.05",
+            "--es",
+            "0.5",
+        ];
+        let error = run_args(&args).unwrap_err();
+        assert_eq!(error.field.as_deref(), Some("k"));
+    }
+
+    #[test]
+    fn derived_effect_size() {
+        let args = [
+            "pa",
+            "power",
+            "--test",
+            "twoProportionsTest",
+            "--p1",
+            "0.3",
+            "--p2",
+            "0.5",
+            "--n",
+            "50",
+            "--alpha",
+            "0.05",
+            "--json",
+        ];
+        assert!(run_args(&args).unwrap().starts_with(r#"{"power":0.418"#));
+    }
+}
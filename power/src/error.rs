@@ -0,0 +1,74 @@
+///
+/// Errors that are returned to the caller instead of aborting the module.
+///
+use serde::Serialize;
+use serde_json::json;
+use serde_json::Value;
+use std::fmt;
+
+/// Machine-readable reason for a failed request.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The request is not valid JSON.
+    InvalidJson,
+    /// A required field is absent.
+    MissingField,
+    /// A field has the wrong JSON type.
+    InvalidType,
+    /// A field has the right type but its value cannot be used.
+    InvalidValue,
+    /// The `test` field names a test that does not exist.
+    UnknownTest,
+    /// The `analysis` field names an analysis that does not exist.
+    UnknownAnalysis,
+    /// A parameter is outside the range where the test is defined.
+    OutOfBounds,
+    /// No value satisfies the requested analysis.
+    NoSolution,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PowerError {
+    pub code: ErrorCode,
+    /// Name of the offending field in the request, if any.
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl PowerError {
+    pub fn new(code: ErrorCode, field: Option<&str>, message: &str) -> Self {
+        Self {
+            code,
+            field: field.map(|field| field.to_string()),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn missing_field(field: &str) -> Self {
+        Self::new(
+            ErrorCode::MissingField,
+            Some(field),
+            &format!("Missing field: {field}"),
+        )
+    }
+
+    pub fn no_solution() -> Self {
+        Self::new(
+            ErrorCode::NoSolution,
+            None,
+            "Unable to find a solution for given input.",
+        )
+    }
+
+    /// Returns the error as `{"error": {"code": ..., "field": ..., "message": ...}}`.
+    pub fn to_json(&self) -> Value {
+        json!({ "error": self })
+    }
+}
+
+impl fmt::Display for PowerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
@@ -0,0 +1,249 @@
+///
+/// Exact tests on discrete test statistics.
+///
+/// Instead of a critical value of a continuous distribution, these tests have a rejection
+/// region of counts whose probability under the null hypothesis is at most α. Because the
+/// counts are discrete, the actual α is usually below the nominal α, and power is not
+/// monotone in the sample size.
+///
+use crate::power::Tail;
+use dist::Binomial;
+
+/// Rejection region that contains all counts of at most `lower` or at least `upper`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Region {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl Region {
+    /// Returns the region of the binomial test with `n` trials and success probability `p0`
+    /// under the null hypothesis.
+    ///
+    /// A one-sided test rejects in the upper tail if `upper` is true and in the lower tail
+    /// otherwise; a two-sided test assigns α/2 to both tails.
+    pub fn binomial(n: f64, p0: f64, tail: &Tail, alpha: f64, upper: bool) -> Region {
+        let null = Binomial::new(n, p0);
+        let (lower_alpha, upper_alpha) = match tail {
+            Tail::OneSided if upper => (0.0, alpha),
+            Tail::OneSided => (alpha, 0.0),
+            Tail::TwoSided => (alpha / 2.0, alpha / 2.0),
+        };
+        // Largest count with P(X <= lower) <= α.
+        let q = null.quantile(lower_alpha, true);
+        let lower = if null.cdf(q, true) <= lower_alpha {
+            q
+        } else {
+            q - 1.0
+        };
+        // Smallest count with P(X >= upper) <= α.
+        let upper = null.quantile(upper_alpha, false) + 1.0;
+        Region { lower, upper }
+    }
+
+    /// Returns the probability of the region for `n` trials with success probability `p`.
+    pub fn binomial_probability(&self, n: f64, p: f64) -> f64 {
+        let d = Binomial::new(n, p.clamp(0.0, 1.0));
+        d.cdf(self.lower, true) + d.cdf(self.upper - 1.0, false)
+    }
+}
+
+/// Relative tolerance for ties in probabilities, as in R's `fisher.test`.
+const RELATIVE_ERROR: f64 = 1.0 + 1e-7;
+
+/// Probabilities below this fraction of the largest one are left out of the enumeration.
+const NEGLIGIBLE: f64 = 1e-16;
+
+/// Returns the conditional distribution of the successes in group 2 of a 2×2 table with
+/// `t` successes in total, for `k` from `first` onwards.
+///
+/// Starting at the mode, the distribution is only extended as long as the probabilities
+/// are not negligible, so the work grows with its standard deviation instead of with `t`.
+fn hypergeometric(n1: f64, n2: f64, t: f64) -> (f64, Vec<f64>) {
+    let first = (t - n1).max(0.0);
+    let last = t.min(n2);
+    let mode = ((t + 1.0) * (n2 + 1.0) / (n1 + n2 + 2.0))
+        .floor()
+        .clamp(first, last);
+    // Ratio of the probabilities of k + 1 and k.
+    let ratio = |k: f64| (t - k) * (n2 - k) / ((k + 1.0) * (n1 - t + k + 1.0));
+    let mut below = vec![];
+    let (mut k, mut weight) = (mode, 1.0);
+    while k > first && weight > NEGLIGIBLE {
+        k -= 1.0;
+        weight /= ratio(k);
+        below.push(weight);
+    }
+    let mut above = vec![];
+    let (mut k, mut weight) = (mode, 1.0);
+    while k < last && weight > NEGLIGIBLE {
+        weight *= ratio(k);
+        k += 1.0;
+        above.push(weight);
+    }
+    let start = mode - below.len() as f64;
+    let mut weights: Vec<f64> = below.into_iter().rev().collect();
+    weights.push(1.0);
+    weights.extend(above);
+    let total: f64 = weights.iter().sum();
+    (start, weights.iter().map(|w| w / total).collect())
+}
+
+/// Returns which outcomes of a conditional distribution Fisher's exact test rejects.
+fn fisher_rejects(probabilities: &[f64], tail: &Tail, alpha: f64, upper: bool) -> Vec<bool> {
+    let len = probabilities.len();
+    let mut rejects = vec![false; len];
+    match tail {
+        Tail::OneSided => {
+            let mut p_value = 0.0;
+            for i in 0..len {
+                // Walk from the extreme end of the tested tail inwards.
+                let i = if upper { len - 1 - i } else { i };
+                p_value += probabilities[i];
+                if p_value > alpha * RELATIVE_ERROR {
+                    break;
+                }
+                rejects[i] = true;
+            }
+        }
+        // The p-value sums the probabilities of all outcomes at most as likely as the
+        // observed one, so walk through the outcomes from the least likely one.
+        Tail::TwoSided => {
+            let mut order: Vec<usize> = (0..len).collect();
+            order.sort_by(|a, b| probabilities[*a].total_cmp(&probabilities[*b]));
+            let mut p_value = 0.0;
+            let mut next = 0;
+            for i in 0..len {
+                let observed = probabilities[order[i]] * RELATIVE_ERROR;
+                while next < len && probabilities[order[next]] <= observed {
+                    p_value += probabilities[order[next]];
+                    next += 1;
+                }
+                if p_value > alpha * RELATIVE_ERROR {
+                    break;
+                }
+                rejects[order[i]] = true;
+            }
+        }
+    }
+    rejects
+}
+
+/// Returns the probabilities of `x` successes of a binomial distribution from `first`
+/// onwards, leaving out the negligible tails.
+fn binomial_probabilities(n: f64, p: f64) -> (f64, Vec<f64>) {
+    let d = Binomial::new(n, p.clamp(0.0, 1.0));
+    let first = d.quantile(NEGLIGIBLE, true);
+    let last = d.quantile(NEGLIGIBLE, false);
+    let mut x = first;
+    let mut probabilities = vec![];
+    while x <= last {
+        probabilities.push(d.pmf(x));
+        x += 1.0;
+    }
+    (first, probabilities)
+}
+
+/// Returns the probability that Fisher's exact test rejects when group 1 has `n1` subjects
+/// with success probability `p1` and group 2 has `n2` subjects with `p2`.
+///
+/// The test is conditional on the total number of successes, but this probability is not:
+/// it sums over all tables. A one-sided test rejects for many successes in group 2 if
+/// `upper` is true and for few otherwise.
+pub fn fisher_probability(
+    n1: f64,
+    n2: f64,
+    p1: f64,
+    p2: f64,
+    tail: &Tail,
+    alpha: f64,
+    upper: bool,
+) -> f64 {
+    let (first1, group1) = binomial_probabilities(n1, p1);
+    let (first2, group2) = binomial_probabilities(n2, p2);
+    let (last1, last2) = (first1 + group1.len() as f64, first2 + group2.len() as f64);
+    let mut probability = 0.0;
+    let mut t = first1 + first2;
+    while t <= (last1 - 1.0) + (last2 - 1.0) {
+        let (first, conditional) = hypergeometric(n1, n2, t);
+        let rejects = fisher_rejects(&conditional, tail, alpha, upper);
+        for (i, reject) in rejects.iter().enumerate() {
+            let (x1, x2) = (t - first - i as f64, first + i as f64);
+            let relevant = (first1..last1).contains(&x1) && (first2..last2).contains(&x2);
+            if *reject && relevant {
+                probability += group1[(x1 - first1) as usize] * group2[(x2 - first2) as usize];
+            }
+        }
+        t += 1.0;
+    }
+    probability
+}
+
+/// Returns the probability that the McNemar test rejects for `n` pairs, of which a
+/// proportion `discordant` is discordant and, of those, a proportion `p12` has a success in
+/// the first measurement only.
+///
+/// Conditional on the number of discordant pairs, the test is a binomial test of
+/// `p12 = 0.5`; this probability sums over the number of discordant pairs.
+pub fn mcnemar_probability(
+    n: f64,
+    discordant: f64,
+    p12: f64,
+    tail: &Tail,
+    alpha: f64,
+    upper: bool,
+) -> f64 {
+    let (first, pairs) = binomial_probabilities(n, discordant);
+    pairs
+        .iter()
+        .enumerate()
+        .map(|(i, probability)| {
+            let d = first + i as f64;
+            let region = Region::binomial(d, 0.5, tail, alpha, upper);
+            probability * region.binomial_probability(d, p12)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binomial_region() {
+        // binom.test rejects 59 or more successes out of 100 at α = 0.05 (one-sided).
+        let region = Region::binomial(100.0, 0.5, &Tail::OneSided, 0.05, true);
+        assert_eq!(
+            region,
+            Region {
+                lower: -1.0,
+                upper: 59.0
+            }
+        );
+        let region = Region::binomial(100.0, 0.5, &Tail::TwoSided, 0.05, true);
+        assert_eq!(
+            region,
+            Region {
+                lower: 39.0,
+                upper: 61.0
+            }
+        );
+        assert!(region.binomial_probability(100.0, 0.5) <= 0.05);
+    }
+
+    #[test]
+    fn fisher_p_values() {
+        // fisher.test(matrix(c(8, 2, 1, 5), 2)) gives p = 0.03497 for this table with
+        // 9 successes out of 10 + 6 subjects.
+        let (first, conditional) = hypergeometric(10.0, 6.0, 9.0);
+        assert_eq!((first, conditional.len()), (0.0, 7));
+        let observed = 1;
+        let p_value: f64 = conditional
+            .iter()
+            .filter(|p| **p <= conditional[observed] * RELATIVE_ERROR)
+            .sum();
+        assert!((p_value - 0.03497).abs() < 1e-5);
+        assert!(fisher_rejects(&conditional, &Tail::TwoSided, 0.05, true)[observed]);
+        assert!(!fisher_rejects(&conditional, &Tail::TwoSided, 0.01, true)[observed]);
+    }
+}
@@ -0,0 +1,349 @@
+///
+/// Arithmetic expressions in the sample size `n` and the effect size `es`.
+///
+/// The generic tests take their noncentrality parameter and degrees of freedom as such
+/// expressions, for example `"n - 2"` or `"sqrt(n / 2) * es"`, so that the sample size and
+/// effect size can be solved for. Expressions support `+`, `-`, `*`, `/`, `^`, parentheses,
+/// and the functions `sqrt`, `exp`, `ln`, and `abs`.
+///
+use serde::de;
+use serde::de::Visitor;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+use std::fmt;
+
+/// Deepest nesting of parentheses, signs, and powers that the parser accepts, so that a
+/// pathological expression returns an error instead of overflowing the stack.
+const MAX_DEPTH: usize = 64;
+
+/// Longest expression that is accepted, which bounds the depth of the parse tree of long
+/// chains such as `n + n + ... + n`, which the parser handles in a loop.
+const MAX_LENGTH: usize = 1000;
+
+/// Expression as written by the user, together with its parse tree.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expression {
+    source: String,
+    node: Node,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Number(f64),
+    N,
+    Es,
+    Negate(Box<Node>),
+    Binary(Operator, Box<Node>, Box<Node>),
+    Call(Function, Box<Node>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Power,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Function {
+    Sqrt,
+    Exp,
+    Ln,
+    Abs,
+}
+
+impl Expression {
+    /// Parses `source`, or returns a message that says what is wrong with it.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        if source.chars().count() > MAX_LENGTH {
+            return Err(format!("expression is longer than {MAX_LENGTH} characters"));
+        }
+        let mut parser = Parser {
+            chars: source.chars().collect(),
+            position: 0,
+            depth: 0,
+        };
+        let node = parser.sum()?;
+        parser.skip_whitespace();
+        match parser.peek() {
+            None => Ok(Self {
+                source: source.to_string(),
+                node,
+            }),
+            Some(c) => Err(format!("unexpected '{c}' in \"{source}\"")),
+        }
+    }
+
+    /// Returns the value at sample size `n` and effect size `es`.
+    pub fn eval(&self, n: f64, es: f64) -> f64 {
+        self.node.eval(n, es)
+    }
+}
+
+impl Node {
+    fn eval(&self, n: f64, es: f64) -> f64 {
+        match self {
+            Node::Number(value) => *value,
+            Node::N => n,
+            Node::Es => es,
+            Node::Negate(node) => -node.eval(n, es),
+            Node::Binary(operator, left, right) => {
+                let (left, right) = (left.eval(n, es), right.eval(n, es));
+                match operator {
+                    Operator::Add => left + right,
+                    Operator::Subtract => left - right,
+                    Operator::Multiply => left * right,
+                    Operator::Divide => left / right,
+                    Operator::Power => left.powf(right),
+                }
+            }
+            Node::Call(function, argument) => {
+                let argument = argument.eval(n, es);
+                match function {
+                    Function::Sqrt => argument.sqrt(),
+                    Function::Exp => argument.exp(),
+                    Function::Ln => argument.ln(),
+                    Function::Abs => argument.abs(),
+                }
+            }
+        }
+    }
+}
+
+impl Serialize for Expression {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+struct ExpressionVisitor;
+
+impl Visitor<'_> for ExpressionVisitor {
+    type Value = Expression;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number or an expression in n and es")
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Expression, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Expression, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Expression, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Expression, E> {
+        Expression::parse(v).map_err(E::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for Expression {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ExpressionVisitor)
+    }
+}
+
+/// Recursive descent parser that builds the parse tree.
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    /// Number of nested calls of `unary`, which every recursion passes through.
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    /// Consumes `c` if it is the next character other than whitespace.
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn sum(&mut self) -> Result<Node, String> {
+        let mut node = self.product()?;
+        loop {
+            let operator = if self.eat('+') {
+                Operator::Add
+            } else if self.eat('-') {
+                Operator::Subtract
+            } else {
+                return Ok(node);
+            };
+            node = Node::Binary(operator, Box::new(node), Box::new(self.product()?));
+        }
+    }
+
+    fn product(&mut self) -> Result<Node, String> {
+        let mut node = self.unary()?;
+        loop {
+            let operator = if self.eat('*') {
+                Operator::Multiply
+            } else if self.eat('/') {
+                Operator::Divide
+            } else {
+                return Ok(node);
+            };
+            node = Node::Binary(operator, Box::new(node), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Node, String> {
+        if self.depth == MAX_DEPTH {
+            return Err("expression is nested too deeply".to_string());
+        }
+        self.depth += 1;
+        let node = if self.eat('-') {
+            self.unary().map(|node| Node::Negate(Box::new(node)))
+        } else {
+            self.power()
+        };
+        self.depth -= 1;
+        node
+    }
+
+    /// Exponentiation binds tighter than a leading minus and is right associative.
+    fn power(&mut self) -> Result<Node, String> {
+        let base = self.primary()?;
+        if self.eat('^') {
+            let exponent = self.unary()?;
+            return Ok(Node::Binary(
+                Operator::Power,
+                Box::new(base),
+                Box::new(exponent),
+            ));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Node, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('(') => {
+                self.position += 1;
+                let node = self.sum()?;
+                if !self.eat(')') {
+                    return Err("missing ')'".to_string());
+                }
+                Ok(node)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => self.number(),
+            Some(c) if c.is_ascii_alphabetic() => self.name(),
+            Some(c) => Err(format!("unexpected '{c}'")),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+
+    fn number(&mut self) -> Result<Node, String> {
+        let start = self.position;
+        while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+            self.position += 1;
+        }
+        // Scientific notation such as 1e-3.
+        if self.peek().is_some_and(|c| c == 'e' || c == 'E') {
+            let mark = self.position;
+            self.position += 1;
+            if self.peek().is_some_and(|c| c == '-' || c == '+') {
+                self.position += 1;
+            }
+            if self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    self.position += 1;
+                }
+            } else {
+                self.position = mark;
+            }
+        }
+        let text: String = self.chars[start..self.position].iter().collect();
+        text.parse()
+            .map(Node::Number)
+            .map_err(|_| format!("invalid number \"{text}\""))
+    }
+
+    fn name(&mut self) -> Result<Node, String> {
+        let start = self.position;
+        while self.peek().is_some_and(|c| c.is_ascii_alphanumeric()) {
+            self.position += 1;
+        }
+        let name: String = self.chars[start..self.position].iter().collect();
+        let function = match name.as_str() {
+            "n" => return Ok(Node::N),
+            "es" => return Ok(Node::Es),
+            "sqrt" => Function::Sqrt,
+            "exp" => Function::Exp,
+            "ln" => Function::Ln,
+            "abs" => Function::Abs,
+            _ => return Err(format!("unknown name \"{name}\"")),
+        };
+        if !self.eat('(') {
+            return Err(format!("missing '(' after \"{name}\""));
+        }
+        let argument = self.sum()?;
+        if !self.eat(')') {
+            return Err("missing ')'".to_string());
+        }
+        Ok(Node::Call(function, Box::new(argument)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(source: &str) -> Result<f64, String> {
+        Expression::parse(source).map(|expression| expression.eval(10.0, 0.5))
+    }
+
+    #[test]
+    fn evaluates_in_n_and_es() {
+        assert_eq!(eval("n - 2"), Ok(8.0));
+        assert_eq!(eval("sqrt(n / 2.5) * es"), Ok(1.0));
+        assert_eq!(eval("es^2 * n"), Ok(2.5));
+        assert_eq!(eval("-2^2"), Ok(-4.0));
+        assert_eq!(eval("2^3^2"), Ok(512.0));
+        assert_eq!(eval(" (n + 2) * 5e-1 "), Ok(6.0));
+        assert_eq!(eval("abs(ln(exp(-3)))"), Ok(3.0));
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        assert!(eval("n -").is_err());
+        assert!(eval("(n").is_err());
+        assert!(eval("n n").is_err());
+        assert!(eval("m + 1").is_err());
+        assert!(eval("sqrt n").is_err());
+    }
+
+    #[test]
+    fn rejects_deeply_nested_expressions() {
+        let nested = |depth| format!("{}n{}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(eval(&nested(10)), Ok(10.0));
+        assert!(eval(&nested(100)).is_err());
+        assert!(eval(&format!("{}n", "-".repeat(100))).is_err());
+        assert!(eval(&format!("{}1", "1^".repeat(100))).is_err());
+        assert_eq!(eval(&vec!["n"; 100].join("+")), Ok(1000.0));
+        assert!(eval(&vec!["n"; 1000].join("+")).is_err());
+    }
+}
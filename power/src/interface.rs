@@ -1,59 +1,333 @@
+use crate::error::ErrorCode;
+use crate::error::PowerError;
+use crate::number::deserialize_f64;
+use crate::number::deserialize_option_f64;
+use crate::power::Allocation;
+use crate::power::Direction;
 use crate::power::Tail;
 use crate::power::TestKind;
 use crate::string::json;
-use crate::string::u8_to_string;
-use crate::string::write_to_ptr;
+use crate::string::read_from_ptr;
+use crate::string::write_to_new_ptr;
+use serde::de;
+use serde::ser;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
 use serde_json::json;
+use serde_json::Map;
 use serde_json::Value;
+use serde_path_to_error::Segment;
+use serde_path_to_error::Track;
 
-enum Analysis {
+/// Quantity that is computed from the other three.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Analysis {
     N,
     Alpha,
     Power,
     ES,
+    /// Computes α and power from `n`, `es` and the ratio β/α, for example when the sample
+    /// size is fixed by the budget.
+    Compromise,
 }
 
-impl Analysis {
-    fn from_str(text: &str) -> Result<Analysis, String> {
-        match text {
-            "n" => Ok(Analysis::N),
-            "alpha" => Ok(Analysis::Alpha),
-            "power" => Ok(Analysis::Power),
-            "es" => Ok(Analysis::ES),
-            _ => Err(format!("Unknown analysis: {}", text)),
+/// A request as sent by the front end to `calculatePower`.
+///
+/// The test and its design parameters are flattened into the request, for example
+/// `{"test": "oneWayANOVA", "k": "5", "analysis": "n", "n": 50, ...}`.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct Request {
+    #[serde(flatten)]
+    pub test: TestKind,
+    pub analysis: Analysis,
+    #[serde(deserialize_with = "deserialize_f64")]
+    pub n: f64,
+    #[serde(deserialize_with = "deserialize_f64")]
+    pub alpha: f64,
+    #[serde(deserialize_with = "deserialize_f64")]
+    pub power: f64,
+    /// Effect size; not needed if the test derives it from its parameters.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_option_f64",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub es: Option<f64>,
+    #[serde(default)]
+    pub tail: Tail,
+    /// Side of the null value searched by the sensitivity analysis.
+    #[serde(default)]
+    pub direction: Direction,
+    /// Ratio β/α of the compromise analysis.
+    #[serde(
+        default,
+        rename = "betaAlphaRatio",
+        deserialize_with = "deserialize_option_f64",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub beta_alpha_ratio: Option<f64>,
+}
+
+/// The result of an analysis.
+///
+/// Only the computed quantity out of `n`, `alpha`, `power` and `es` is set, or both `alpha`
+/// and `power` for the compromise analysis; the other fields describe the test statistic at
+/// the resulting design.
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alpha: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub power: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub es: Option<f64>,
+    /// Power at the rounded-up sample size of the a priori analysis.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual_power: Option<f64>,
+    /// Size of group 1 at the sample size of the a priori analysis for two-group tests.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n1: Option<i64>,
+    /// Size of group 2 at the sample size of the a priori analysis for two-group tests.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n2: Option<i64>,
+    /// Noncentrality parameter of the alternative distribution; exact tests have none.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ncp: Option<f64>,
+    /// Degrees of freedom of the test statistic.
+    pub df: Vec<f64>,
+    /// Critical value of the test statistic under the null hypothesis.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub critical_value: Option<f64>,
+    /// Probability of the rejection region under the null hypothesis for exact tests.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual_alpha: Option<f64>,
+    /// Expected number of discordant pairs for the McNemar test.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discordant_pairs: Option<f64>,
+    /// Expected number of events for survival tests.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub events: Option<f64>,
+}
+
+/// Fields of a request that are not parameters of the test.
+const REQUEST_FIELDS: [&str; 8] = [
+    "analysis",
+    "n",
+    "alpha",
+    "power",
+    "es",
+    "tail",
+    "direction",
+    "betaAlphaRatio",
+];
+
+/// Returns the field that serde names in messages such as "missing field `k`".
+fn named_field<'a>(message: &'a str, prefix: &str) -> Option<&'a str> {
+    message.strip_prefix(prefix)?.split('`').next()
+}
+
+fn request_error(error: serde_path_to_error::Error<serde_json::Error>) -> PowerError {
+    let message = error.inner().to_string();
+    if let Some(field) = named_field(&message, "missing field `") {
+        return PowerError::missing_field(field);
+    }
+    let field = match error.path().iter().next_back() {
+        Some(Segment::Map { key }) => Some(key.as_str()),
+        // Unknown fields of a test with a flattened allocation have no path.
+        _ => named_field(&message, "unknown field `"),
+    };
+    let (code, field) = if message.starts_with("unknown variant") {
+        match field {
+            Some("analysis") => (ErrorCode::UnknownAnalysis, field),
+            Some(_) => (ErrorCode::InvalidValue, field),
+            // Only the name of the test is a variant at the top level.
+            None => (ErrorCode::UnknownTest, Some("test")),
+        }
+    } else if message.starts_with("invalid type") {
+        (ErrorCode::InvalidType, field)
+    } else {
+        (ErrorCode::InvalidValue, field)
+    };
+    PowerError::new(code, field, &message)
+}
+
+/// Returns the test named by the `test` field with the other fields as its parameters.
+///
+/// Serde loses the path to an error inside internally tagged data, so the parameters are
+/// deserialized in the externally tagged form of `TestKind`.
+fn test_from_parameters(mut parameters: Map<String, Value>) -> Result<TestKind, PowerError> {
+    let name = match parameters.remove("test") {
+        Some(Value::String(name)) => name,
+        Some(_) => {
+            return Err(PowerError::new(
+                ErrorCode::InvalidType,
+                Some("test"),
+                "The test should be a string",
+            ))
+        }
+        None => return Err(PowerError::missing_field("test")),
+    };
+    // Tests without parameters are plain names in the externally tagged form.
+    if let Ok(test) = TestKind::deserialize(Value::String(name.clone())) {
+        return match parameters.keys().next() {
+            Some(field) => Err(PowerError::new(
+                ErrorCode::InvalidValue,
+                Some(field),
+                &format!("unknown field `{field}`, the test has no parameters"),
+            )),
+            None => Ok(test),
+        };
+    }
+    let external = Value::Object(Map::from_iter([(name, Value::Object(parameters.clone()))]));
+    let mut track = Track::new();
+    TestKind::deserialize(serde_path_to_error::Deserializer::new(external, &mut track)).map_err(
+        |error| {
+            let error = request_error(serde_path_to_error::Error::new(track.path(), error));
+            if error.field.is_some() {
+                return error;
+            }
+            // The path also ends at the test for errors in the flattened allocation of
+            // two-group tests, so look for them there.
+            match serde_path_to_error::deserialize::<_, Allocation>(Value::Object(parameters)) {
+                Err(allocation_error) => request_error(allocation_error),
+                Ok(_) => error,
+            }
+        },
+    )
+}
+
+impl Serialize for TestKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let external =
+            TestKind::serialize(self, serde_json::value::Serializer).map_err(ser::Error::custom)?;
+        let mut object = Map::new();
+        match external {
+            Value::Object(variant) => {
+                for (name, parameters) in variant {
+                    object.insert("test".to_string(), Value::String(name));
+                    if let Value::Object(parameters) = parameters {
+                        object.extend(parameters);
+                    }
+                }
+            }
+            name => {
+                object.insert("test".to_string(), name);
+            }
         }
+        object.serialize(serializer)
     }
 }
 
-struct Received {
-    test: TestKind,
-    analysis: Analysis,
-    n: f64,
-    alpha: f64,
-    power: f64,
-    es: f64,
+impl<'de> Deserialize<'de> for TestKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let parameters = Map::deserialize(deserializer)?;
+        test_from_parameters(parameters).map_err(de::Error::custom)
+    }
 }
 
-impl Received {
-    fn from_json(data: &Value) -> Result<Received, String> {
-        let test = TestKind::from_str(data["test"].as_str().unwrap(), data)?;
-        let analysis = Analysis::from_str(data["analysis"].as_str().unwrap())?;
-        let n = data["n"].as_f64().unwrap();
-        let alpha = data["alpha"].as_f64().unwrap();
-        let power = data["power"].as_f64().unwrap();
-        let es = data["es"].as_f64().unwrap();
-        Ok(Received {
-            test,
-            analysis,
-            n,
-            alpha,
-            power,
-            es,
-        })
+impl Request {
+    pub fn from_json(text: &str) -> Result<Request, PowerError> {
+        Request::from_value(parse_json(text)?)
+    }
+
+    pub fn from_value(data: Value) -> Result<Request, PowerError> {
+        let request: Request = serde_path_to_error::deserialize(&data).or_else(|error| {
+            // Errors in the flattened test have no path, so deserialize the test on its own.
+            if error.path().iter().next().is_none() {
+                if let Some(object) = data.as_object() {
+                    let parameters = object
+                        .iter()
+                        .filter(|(key, _)| !REQUEST_FIELDS.contains(&key.as_str()))
+                        .map(|(key, value)| (key.clone(), value.clone()))
+                        .collect();
+                    test_from_parameters(parameters)?;
+                }
+            }
+            Err(request_error(error))
+        })?;
+        request.test.validate()?;
+        Ok(request)
+    }
+
+    /// Returns the ratio β/α of the compromise analysis, which should be positive.
+    fn beta_alpha_ratio(&self) -> Result<f64, PowerError> {
+        // The range is checked by the compromise analysis.
+        self.beta_alpha_ratio
+            .ok_or_else(|| PowerError::missing_field("betaAlphaRatio"))
+    }
+
+    pub fn calculate(&self) -> Result<Response, PowerError> {
+        let mut test = self.test.clone();
+        let tail = self.tail.clone();
+        let (mut n, mut alpha) = (self.n, self.alpha);
+        // The sensitivity analysis computes the effect size, so it need not be given.
+        let mut es = match self.analysis {
+            Analysis::ES => 0.0,
+            _ => test.effect_size(self.es)?,
+        };
+        let mut response = Response::default();
+        match self.analysis {
+            Analysis::N => {
+                let (design, rounded) = test.n(tail.clone(), self.alpha, self.power, es)?;
+                test = design;
+                n = rounded as f64;
+                response.n = Some(rounded);
+                if let Some(Allocation {
+                    n1: Some(n1),
+                    n2: Some(n2),
+                    ..
+                }) = test.allocation()
+                {
+                    response.n1 = Some(*n1 as i64);
+                    response.n2 = Some(*n2 as i64);
+                }
+                let actual_power = test.power(tail.clone(), n, self.alpha, es)?;
+                response.actual_power = Some(round(actual_power, 3));
+            }
+            Analysis::Alpha => {
+                alpha = test.alpha(tail.clone(), self.n, self.power, es)?;
+                response.alpha = Some(round(alpha, 3));
+            }
+            Analysis::Power => {
+                let power = test.power(tail.clone(), self.n, self.alpha, es)?;
+                response.power = Some(round(power, 3));
+            }
+            Analysis::ES => {
+                es = test.es(
+                    tail.clone(),
+                    self.direction.clone(),
+                    self.n,
+                    self.alpha,
+                    self.power,
+                )?;
+                response.es = Some(round(es, 3));
+            }
+            Analysis::Compromise => {
+                let ratio = self.beta_alpha_ratio()?;
+                alpha = test.compromise(tail.clone(), self.n, es, ratio)?;
+                response.alpha = Some(round(alpha, 3));
+                let power = test.power(tail.clone(), self.n, alpha, es)?;
+                response.power = Some(round(power, 3));
+            }
+        };
+        let details = test.details(tail, n, alpha, es)?;
+        response.ncp = details.ncp.map(|ncp| round(ncp, 3));
+        response.df = details.df.iter().map(|df| round(*df, 3)).collect();
+        response.critical_value = details.critical_value.map(|value| round(value, 3));
+        response.actual_alpha = details.actual_alpha.map(|alpha| round(alpha, 3));
+        response.discordant_pairs = details.discordant_pairs.map(|pairs| round(pairs, 3));
+        response.events = details.events.map(|events| round(events, 3));
+        Ok(response)
     }
 }
 
-fn round(x: f64, decimals: u32) -> f64 {
+pub fn round(x: f64, decimals: u32) -> f64 {
     let factor = i64::checked_pow(10, decimals);
     match factor {
         Some(number) => (x * number as f64).round() / number as f64,
@@ -66,39 +340,44 @@ fn rounding() {
     assert_eq!(round(1.234, 2), 1.23);
 }
 
+fn parse_json(text: &str) -> Result<Value, PowerError> {
+    match json(text) {
+        Ok(data) if data.is_object() => Ok(data),
+        Ok(_) => Err(PowerError::new(
+            ErrorCode::InvalidJson,
+            None,
+            "Expected a JSON object",
+        )),
+        // The error names the reason and its line and column.
+        Err(error) => Err(PowerError::new(
+            ErrorCode::InvalidJson,
+            None,
+            &format!("Failed to parse json: {error}"),
+        )),
+    }
+}
+
+/// Returns the result of the analysis or, if the request is invalid, an error object.
 pub fn handle_received(text: &str) -> Value {
-    let data: Value = json(text).unwrap();
-    let recv = Received::from_json(&data).unwrap();
-    let tail = match data.get("tail") {
-        Some(_) => Tail::from_json(&data).unwrap(),
-        None => Tail::OneSided,
-    };
-    let test = recv.test;
-    match recv.analysis {
-        Analysis::N => {
-            let n = test.n(tail, recv.alpha, recv.power, recv.es);
-            json!({"n": n})
-        }
-        Analysis::Alpha => {
-            let alpha = round(test.alpha(tail, recv.n, recv.power, recv.es), 3);
-            json!({"alpha": alpha})
-        }
-        Analysis::Power => {
-            println!("{tail:?}");
-            println!("{}", test.power(tail.clone(), recv.n, recv.alpha, recv.es));
-            let power = round(test.power(tail, recv.n, recv.alpha, recv.es), 3);
-            json!({"power": power})
-        }
-        Analysis::ES => {
-            let es = round(test.es(tail, recv.n, recv.alpha, recv.power), 3);
-            json!({"es": es})
-        }
+    match Request::from_json(text).and_then(|request| request.calculate()) {
+        Ok(response) => json!(response),
+        Err(error) => error.to_json(),
     }
 }
 
+/// Computes the analysis for the `len`-byte JSON request at `ptr`.
+///
+/// Returns a length-prefixed response that the caller frees with `dealloc`; see
+/// `string.rs` for the layout.
+///
+/// # Safety
+///
+/// `ptr` must point to `len` initialized bytes.
 #[no_mangle]
-pub extern "C" fn calculatePower(ptr: *mut u8) {
-    let text = unsafe { u8_to_string(ptr) };
-    let result = handle_received(&text);
-    write_to_ptr(ptr, &result.to_string());
+pub unsafe extern "C" fn calculatePower(ptr: *const u8, len: usize) -> *mut u8 {
+    let result = match read_from_ptr(ptr, len) {
+        Ok(text) => handle_received(&text),
+        Err(error) => error.to_json(),
+    };
+    write_to_new_ptr(&result.to_string())
 }
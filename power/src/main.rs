@@ -1,8 +1,31 @@
+#[cfg(not(target_os = "emscripten"))]
+mod cli;
+mod error;
+mod exact;
+mod expression;
 mod interface;
+mod number;
 mod power;
+mod solve;
 mod string;
 
 #[cfg(test)]
 mod tests;
 
+#[cfg(not(target_os = "emscripten"))]
+pub fn main() {
+    use clap::Parser;
+
+    let cli = cli::Cli::parse();
+    match cli::run(&cli) {
+        Ok(output) => println!("{output}"),
+        Err(error) => {
+            eprintln!("error: {error}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// The wasm module only exports functions; there is nothing to run on load.
+#[cfg(target_os = "emscripten")]
 pub fn main() {}
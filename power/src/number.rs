@@ -0,0 +1,123 @@
+///
+/// Deserializers for numeric fields that may be sent as JSON numbers or as numeric strings.
+///
+/// The front end sends the design parameters from text inputs (`"k": "3"`) and the analysis
+/// parameters as numbers (`"n": 50`), so both forms are accepted for every numeric field.
+///
+use serde::de;
+use serde::de::Unexpected;
+use serde::de::Visitor;
+use serde::Deserialize;
+use serde::Deserializer;
+use std::fmt;
+
+struct F64Visitor;
+
+impl Visitor<'_> for F64Visitor {
+    type Value = f64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number or a numeric string")
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<f64, E> {
+        Ok(v)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<f64, E> {
+        Ok(v as f64)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<f64, E> {
+        Ok(v as f64)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<f64, E> {
+        v.trim()
+            .parse()
+            .map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+struct I64Visitor;
+
+impl Visitor<'_> for I64Visitor {
+    type Value = i64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an integer or an integer string")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<i64, E> {
+        Ok(v)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<i64, E> {
+        i64::try_from(v).map_err(|_| E::invalid_value(Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<i64, E> {
+        if v.fract() == 0.0 && v.abs() < i64::MAX as f64 {
+            Ok(v as i64)
+        } else {
+            Err(E::invalid_value(Unexpected::Float(v), &self))
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<i64, E> {
+        v.trim()
+            .parse()
+            .map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+pub fn deserialize_f64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    deserializer.deserialize_any(F64Visitor)
+}
+
+pub fn deserialize_i64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    deserializer.deserialize_any(I64Visitor)
+}
+
+/// Like `deserialize_f64`, for fields that may be absent; use with `#[serde(default)]`.
+pub fn deserialize_option_f64<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<f64>, D::Error> {
+    #[derive(Deserialize)]
+    struct Number(#[serde(deserialize_with = "deserialize_f64")] f64);
+
+    let number = Option::<Number>::deserialize(deserializer)?;
+    Ok(number.map(|Number(x)| x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Deserialize)]
+    struct Fields {
+        #[serde(deserialize_with = "deserialize_f64")]
+        x: f64,
+        #[serde(deserialize_with = "deserialize_i64")]
+        k: i64,
+        #[serde(default, deserialize_with = "deserialize_option_f64")]
+        y: Option<f64>,
+    }
+
+    #[test]
+    fn numbers_and_strings() {
+        let fields: Fields = serde_json::from_value(json!({"x": 0.5, "k": 3})).unwrap();
+        assert_eq!((fields.x, fields.k), (0.5, 3));
+        let fields: Fields = serde_json::from_value(json!({"x": "0.5", "k": "3"})).unwrap();
+        assert_eq!((fields.x, fields.k), (0.5, 3));
+        let fields: Fields = serde_json::from_value(json!({"x": 2, "k": 3.0})).unwrap();
+        assert_eq!((fields.x, fields.k), (2.0, 3));
+        assert_eq!(fields.y, None);
+        let fields: Fields = serde_json::from_value(json!({"x": 1, "k": 3, "y": "4"})).unwrap();
+        assert_eq!(fields.y, Some(4.0));
+        assert!(serde_json::from_value::<Fields>(json!({"x": "a", "k": 3})).is_err());
+        assert!(serde_json::from_value::<Fields>(json!({"x": 1, "k": 3.5})).is_err());
+        assert!(serde_json::from_value::<Fields>(json!({"x": true, "k": 3})).is_err());
+    }
+}
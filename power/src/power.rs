@@ -1,219 +1,1992 @@
 #![allow(clippy::upper_case_acronyms)]
+use crate::error::ErrorCode;
+use crate::error::PowerError;
+use crate::exact::fisher_probability;
+use crate::exact::mcnemar_probability;
+use crate::exact::Region;
+use crate::expression::Expression;
+use crate::number::deserialize_f64;
+use crate::number::deserialize_i64;
+use crate::number::deserialize_option_f64;
+use crate::solve::find_root;
+use crate::solve::find_root_between;
+use dist::tost::ptost;
 use dist::Dist;
 use dist::NoncentralChisq;
 use dist::NoncentralF;
 use dist::NoncentralT;
-use roots::find_root_regula_falsi;
-use roots::SimpleConvergency;
-use serde_json::Value;
+use dist::Normal;
+use dist::SampleCorrelation;
+use dist::Scaled;
+use serde::de;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+
+/// Largest sample size that the a priori analysis considers.
+const MAX_N: f64 = 1e7;
+/// Search range for the sensitivity analysis.
+const MIN_ES: f64 = 0.001;
+const MAX_ES: f64 = 100.0;
+/// Largest absolute correlation; larger effect sizes from the search are clamped to it.
+const MAX_CORRELATION: f64 = 1.0 - 1e-9;
 
 /// Supertype for all test types.
 ///
 /// See the G*Power 3 paper for the equations for the distribution parameters
 /// (https://doi.org/10.3758/BF03193146).
+///
+/// The derived serde functions are externally tagged, `{"oneWayANOVA": {"k": "5"}}`, so that
+/// errors can be traced to a parameter; the `Serialize` and `Deserialize` implementations in
+/// `interface.rs` use the `{"test": "oneWayANOVA", "k": "5"}` form of the requests.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(
+    remote = "Self",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase",
+    deny_unknown_fields
+)]
 pub enum TestKind {
     /// Means: Difference from constant (one sample case).
     OneSampleTTest,
     /// Means: Difference between two independent means (two groups).
-    IndependentSamplesTTest,
+    ///
+    /// The sample size `n` is the total over both groups, which are split according to the
+    /// allocation ratio unless the group sizes are given.
+    IndependentSamplesTTest {
+        #[serde(flatten)]
+        allocation: Allocation,
+    },
+    /// Means: Difference between two independent means with unequal variances (Welch test).
+    ///
+    /// The effect size is the mean difference divided by the root mean square of the two
+    /// standard deviations, sqrt((σ1² + σ2²) / 2). The variances enter through their ratio,
+    /// which is given directly or follows from the standard deviations.
+    IndependentSamplesWelchTTest {
+        #[serde(flatten)]
+        allocation: Allocation,
+        /// Standard deviation of group 1.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        sd1: Option<f64>,
+        /// Standard deviation of group 2.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        sd2: Option<f64>,
+        /// Variance ratio σ2²/σ1²; takes precedence over the standard deviations.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        var_ratio: Option<f64>,
+    },
+    /// Means: Difference between two dependent means (matched pairs).
+    ///
+    /// The effect size is d_z, the mean of the differences divided by their standard
+    /// deviation. If the means are given, d_z is derived from the means, the standard
+    /// deviations, and the correlation between the two measurements instead.
+    PairedSamplesTTest {
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        mean1: Option<f64>,
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        mean2: Option<f64>,
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        sd1: Option<f64>,
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        sd2: Option<f64>,
+        /// Correlation between the two measurements.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        rho: Option<f64>,
+    },
+    /// Means: Equivalence of a mean to a constant (one sample case).
+    ///
+    /// The effect size is the true standardized difference d. Equivalence is shown by two
+    /// one-sided tests (TOST) at level α against the margins, so the tail is not used.
+    OneSampleEquivalenceTest {
+        /// Lower equivalence margin, or the non-inferiority margin, as an effect size.
+        #[serde(deserialize_with = "deserialize_f64")]
+        lower: f64,
+        /// Upper equivalence margin as an effect size; not used for non-inferiority.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        upper: Option<f64>,
+        #[serde(default)]
+        mode: EquivalenceMode,
+    },
+    /// Means: Equivalence of two dependent means (matched pairs), with the effect size d_z.
+    PairedSamplesEquivalenceTest {
+        /// Lower equivalence margin, or the non-inferiority margin, as an effect size.
+        #[serde(deserialize_with = "deserialize_f64")]
+        lower: f64,
+        /// Upper equivalence margin as an effect size; not used for non-inferiority.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        upper: Option<f64>,
+        #[serde(default)]
+        mode: EquivalenceMode,
+    },
+    /// Means: Equivalence of two independent means (two groups).
+    IndependentSamplesEquivalenceTest {
+        #[serde(flatten)]
+        allocation: Allocation,
+        /// Lower equivalence margin, or the non-inferiority margin, as an effect size.
+        #[serde(deserialize_with = "deserialize_f64")]
+        lower: f64,
+        /// Upper equivalence margin as an effect size; not used for non-inferiority.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        upper: Option<f64>,
+        #[serde(default)]
+        mode: EquivalenceMode,
+    },
+    /// Means: Wilcoxon signed-rank test (one sample case).
+    ///
+    /// Power is that of the one-sample t-test with the sample size multiplied by the
+    /// asymptotic relative efficiency (ARE) for the parent distribution.
+    WilcoxonSignedRankTest {
+        #[serde(default)]
+        parent: ParentDistribution,
+    },
+    /// Means: Wilcoxon signed-rank test (matched pairs), with the effect size d_z.
+    WilcoxonSignedRankPairedTest {
+        #[serde(default)]
+        parent: ParentDistribution,
+    },
+    /// Means: Wilcoxon–Mann–Whitney test of two independent groups.
+    ///
+    /// Power is that of the independent samples t-test with both group sizes multiplied by
+    /// the asymptotic relative efficiency (ARE) for the parent distribution.
+    MannWhitneyTest {
+        #[serde(flatten)]
+        allocation: Allocation,
+        #[serde(default)]
+        parent: ParentDistribution,
+    },
+    /// Variance: Difference from constant (one sample case).
+    ///
+    /// The effect size is the ratio σ²/σ0² of the true to the hypothesized variance. A
+    /// one-sided test with a ratio below 1 rejects in the lower tail.
+    VarianceTest,
+    /// Variance: Inequality of two variances (F test).
+    ///
+    /// The effect size is the variance ratio σ2²/σ1². A one-sided test with a ratio below 1
+    /// rejects in the lower tail.
+    TwoVariancesTest {
+        #[serde(flatten)]
+        allocation: Allocation,
+    },
+    /// Exact: Proportion difference from constant (binomial test, one sample case).
+    ///
+    /// The effect size is g = p1 - p0, where p1 is the success probability under the
+    /// alternative hypothesis. A one-sided test with a negative g rejects in the lower tail.
+    ExactBinomialTest {
+        /// Success probability under the null hypothesis.
+        #[serde(deserialize_with = "deserialize_f64")]
+        p0: f64,
+    },
+    /// Proportions: Difference between two independent proportions.
+    ///
+    /// The effect size is Cohen's h = 2 asin √p2 - 2 asin √p1. If p2 is given, h is derived
+    /// from the two proportions instead. All methods except `arcsine` need p1.
+    TwoProportionsTest {
+        #[serde(flatten)]
+        allocation: Allocation,
+        /// Proportion in group 1.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        p1: Option<f64>,
+        /// Proportion in group 2.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        p2: Option<f64>,
+        #[serde(default)]
+        method: ProportionsMethod,
+    },
+    /// Proportions: Inequality, two dependent groups (McNemar).
+    ///
+    /// The effect size is the odds ratio p12/p21 of the two kinds of discordant pairs. If the
+    /// discordant proportions p12 and p21 are given, the odds ratio and the proportion of
+    /// discordant pairs are derived from them instead.
+    McNemarTest {
+        /// Proportion of discordant pairs, p12 + p21.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        prop_discordant: Option<f64>,
+        /// Proportion of pairs with a success in the first measurement only.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        p12: Option<f64>,
+        /// Proportion of pairs with a success in the second measurement only.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        p21: Option<f64>,
+        #[serde(default)]
+        method: McNemarMethod,
+    },
+    /// Correlation: Bivariate normal model (one sample case).
+    ///
+    /// The effect size is the correlation ρ under the alternative hypothesis, which is tested
+    /// against ρ0. A one-sided test with ρ below ρ0 rejects in the lower tail.
+    CorrelationTest {
+        /// Correlation under the null hypothesis.
+        #[serde(default, deserialize_with = "deserialize_f64")]
+        rho0: f64,
+        #[serde(default)]
+        method: CorrelationMethod,
+    },
+    /// Correlations: Difference between two independent Pearson r's.
+    ///
+    /// The effect size is Cohen's q = atanh ρ2 - atanh ρ1. If both correlations are given, q
+    /// is derived from them instead.
+    TwoCorrelationsTest {
+        #[serde(flatten)]
+        allocation: Allocation,
+        /// Correlation in group 1.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        rho1: Option<f64>,
+        /// Correlation in group 2.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        rho2: Option<f64>,
+    },
+    /// Correlations: Two dependent Pearson r's (common index).
+    ///
+    /// Steiger's z-test of ρ12 = ρ13, where ρ12 is the correlation under the null hypothesis.
+    /// The effect size is Cohen's q = atanh ρ13 - atanh ρ12. If ρ13 is given, q is derived
+    /// from it instead.
+    DependentCorrelationsCommonIndexTest {
+        #[serde(deserialize_with = "deserialize_f64")]
+        rho12: f64,
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        rho13: Option<f64>,
+        #[serde(deserialize_with = "deserialize_f64")]
+        rho23: f64,
+    },
+    /// Correlations: Two dependent Pearson r's (no common index).
+    ///
+    /// Steiger's z-test of ρ12 = ρ34 for four variables measured on the same sample, where
+    /// ρ12 is the correlation under the null hypothesis. The effect size is Cohen's
+    /// q = atanh ρ34 - atanh ρ12. If ρ34 is given, q is derived from it instead.
+    DependentCorrelationsNoCommonIndexTest {
+        #[serde(deserialize_with = "deserialize_f64")]
+        rho12: f64,
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        rho34: Option<f64>,
+        #[serde(deserialize_with = "deserialize_f64")]
+        rho13: f64,
+        #[serde(deserialize_with = "deserialize_f64")]
+        rho14: f64,
+        #[serde(deserialize_with = "deserialize_f64")]
+        rho23: f64,
+        #[serde(deserialize_with = "deserialize_f64")]
+        rho24: f64,
+    },
+    /// Survival: Log-rank test or Cox regression of two groups.
+    ///
+    /// The effect size is the hazard ratio λ2/λ1 of group 2 to group 1. Power depends on the
+    /// expected number of events, which follows from the event probabilities or, with
+    /// exponential survival and uniform accrual, from the median survival of group 1 and the
+    /// accrual and follow-up times.
+    SurvivalTest {
+        #[serde(flatten)]
+        allocation: Allocation,
+        /// Median survival time in group 1.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        median1: Option<f64>,
+        /// Length of the accrual period, over which subjects enter uniformly.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        accrual: Option<f64>,
+        /// Follow-up time after the end of accrual.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        follow_up: Option<f64>,
+        /// Probability that a subject in group 1 has an event during the study; takes
+        /// precedence over the median survival.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        event_prob1: Option<f64>,
+        /// Probability that a subject in group 2 has an event during the study.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        event_prob2: Option<f64>,
+        #[serde(default)]
+        method: SurvivalMethod,
+    },
     /// Goodness-of-fit tests: Contingency tables.
     GoodnessOfFitChisqTest {
         /// Degrees of freedom.
+        #[serde(deserialize_with = "deserialize_i64")]
         df: i64,
     },
     /// Linear multiple regression: Fixed model, R^2 deviation from zero.
     DeviationFromZeroMultipleRegression {
         /// Number of predictors (#A).
+        #[serde(deserialize_with = "deserialize_i64")]
         n_predictors: i64,
     },
     /// Multiple regression: increase of R^2.
     IncreaseMultipleRegression {
         /// Total number of predictors (#A + #B).
+        #[serde(deserialize_with = "deserialize_i64")]
         p: i64,
         /// Number of tested predictors (#B).
+        #[serde(deserialize_with = "deserialize_i64")]
         q: i64,
     },
+    /// Logistic regression: test of one predictor with the others held fixed.
+    ///
+    /// The effect size is the odds ratio of a unit increase of the predictor. If p1 is given,
+    /// the odds ratio is derived from p0 and p1 instead. The other predictors reduce the
+    /// effective sample size by the factor 1 - R² (Hsieh et al., 1998).
+    LogisticRegression {
+        /// Event probability at the mean of a normal predictor or at zero for a binary one.
+        #[serde(deserialize_with = "deserialize_f64")]
+        p0: f64,
+        /// Event probability one unit above that.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        p1: Option<f64>,
+        /// Squared multiple correlation of the predictor with the other predictors.
+        #[serde(default, deserialize_with = "deserialize_f64")]
+        r2_other: f64,
+        #[serde(default)]
+        x_distribution: PredictorDistribution,
+        /// Standard deviation of a normal predictor; defaults to 1.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        x_sd: Option<f64>,
+        /// Proportion of ones of a binary predictor; defaults to 0.5.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        x_prop: Option<f64>,
+        #[serde(default)]
+        method: LogisticMethod,
+    },
+    /// Poisson regression: test of one predictor with the others held fixed.
+    ///
+    /// The effect size is the rate ratio exp(β1) of a unit increase of the predictor. The
+    /// other predictors reduce the effective sample size by the factor 1 - R².
+    PoissonRegression {
+        /// Event rate per unit of exposure at x = 0, exp(β0).
+        #[serde(deserialize_with = "deserialize_f64")]
+        base_rate: f64,
+        /// Mean exposure time; defaults to 1.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        exposure: Option<f64>,
+        /// Squared multiple correlation of the predictor with the other predictors.
+        #[serde(default, deserialize_with = "deserialize_f64")]
+        r2_other: f64,
+        #[serde(default)]
+        x_distribution: PredictorDistribution,
+        /// Mean of a normal predictor; defaults to 0.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        x_mean: Option<f64>,
+        /// Standard deviation of a normal predictor; defaults to 1.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        x_sd: Option<f64>,
+        /// Proportion of ones of a binary predictor; defaults to 0.5.
+        #[serde(
+            default,
+            deserialize_with = "deserialize_option_f64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        x_prop: Option<f64>,
+    },
     /// ANCOVA: Fixed effects, main effects and interattions.
+    #[serde(rename = "ANCOVA")]
     ANCOVA {
         /// Number of groups.
         /// In factorial ANCOVA is A*B*C.
+        #[serde(deserialize_with = "deserialize_i64")]
         k: i64,
         /// Degrees of freedom of the tested effect.
         /// (number of factor levels - 1).
         /// In ANCOVA it depends on what factor you are interested,
         /// e.g. A, B, or C.
+        #[serde(deserialize_with = "deserialize_i64")]
         q: i64,
         /// Number of covariates.
+        #[serde(deserialize_with = "deserialize_i64")]
         p: i64,
     },
     /// ANOVA: Fixed effects, omnibus, one-way.
     OneWayANOVA {
         /// Number of groups.
+        #[serde(deserialize_with = "deserialize_i64")]
         k: i64,
     },
     /// ANOVA: Fixed effects, special, main effects and interactions.
     TwoWayANOVA {
         /// Total number of cells in the design.
+        #[serde(deserialize_with = "deserialize_i64")]
         k: i64,
         /// Degrees of freedom of the tested effect.
         /// (number of factor levels - 1).
+        #[serde(deserialize_with = "deserialize_i64")]
         q: i64,
     },
+    /// Hotelling's T²: Difference of a mean vector from a constant (one sample case).
+    ///
+    /// The effect size is the Mahalanobis distance Δ of the mean vector from the constant.
+    OneSampleHotellingT2Test {
+        /// Number of response variables.
+        #[serde(deserialize_with = "deserialize_i64")]
+        n_responses: i64,
+    },
+    /// Hotelling's T²: Difference between two independent mean vectors.
+    ///
+    /// The effect size is the Mahalanobis distance Δ between the two mean vectors.
+    TwoSampleHotellingT2Test {
+        /// Number of response variables.
+        #[serde(deserialize_with = "deserialize_i64")]
+        n_responses: i64,
+        #[serde(flatten)]
+        allocation: Allocation,
+    },
+    /// MANOVA: Global effects.
+    ///
+    /// The effect size is f = sqrt(f²), where f² follows from the multivariate statistic;
+    /// for Pillai's V with s = min(#responses, k - 1), f² = V / (s - V).
+    GlobalMANOVA {
+        /// Number of groups.
+        #[serde(deserialize_with = "deserialize_i64")]
+        k: i64,
+        /// Number of response variables.
+        #[serde(deserialize_with = "deserialize_i64")]
+        n_responses: i64,
+        #[serde(default)]
+        statistic: MultivariateStatistic,
+    },
     /// ANOVA: Repeated measures, between factors.
     BetweenRepeatedANOVA {
         /// Levels of between factor.
+        #[serde(deserialize_with = "deserialize_i64")]
         k: i64,
         /// Levels of repeated measures.
+        #[serde(deserialize_with = "deserialize_i64")]
         m: i64,
         /// Correlation among repeated measures.
+        #[serde(deserialize_with = "deserialize_f64")]
         rho: f64,
     },
     /// ANOVA: Repeated measures, within factors.
     WithinRepeatedANOVA {
         /// Levels of between factor.
+        #[serde(deserialize_with = "deserialize_i64")]
         k: i64,
         /// Levels of repeated measures.
+        #[serde(deserialize_with = "deserialize_i64")]
         m: i64,
         /// Correlation among repeated measures.
+        #[serde(deserialize_with = "deserialize_f64")]
         rho: f64,
         /// Nonsphericity correction.
+        #[serde(deserialize_with = "deserialize_f64")]
         epsilon: f64,
     },
     /// ANOVA: Repeated measures, within-between interactions.
     WithinBetweenRepeatedANOVA {
         /// Levels of between factor.
+        #[serde(deserialize_with = "deserialize_i64")]
         k: i64,
         /// Levels of repeated measures.
+        #[serde(deserialize_with = "deserialize_i64")]
         m: i64,
         /// Correlation among repeated measures.
+        #[serde(deserialize_with = "deserialize_f64")]
         rho: f64,
         /// Nonsphericity correction.
+        #[serde(deserialize_with = "deserialize_f64")]
         epsilon: f64,
     },
+    /// MANOVA: Repeated measures, between factors.
+    ///
+    /// The between factor is tested on the mean of the measurements, which is a single
+    /// response, so this is the F test of `BetweenRepeatedANOVA` with the effect size f(V).
+    BetweenRepeatedMANOVA {
+        /// Levels of between factor.
+        #[serde(deserialize_with = "deserialize_i64")]
+        k: i64,
+        /// Levels of repeated measures.
+        #[serde(deserialize_with = "deserialize_i64")]
+        m: i64,
+    },
+    /// MANOVA: Repeated measures, within factors.
+    ///
+    /// The m - 1 contrasts between the measurements are the responses, so no sphericity is
+    /// assumed.
+    WithinRepeatedMANOVA {
+        /// Levels of between factor.
+        #[serde(deserialize_with = "deserialize_i64")]
+        k: i64,
+        /// Levels of repeated measures.
+        #[serde(deserialize_with = "deserialize_i64")]
+        m: i64,
+    },
+    /// MANOVA: Repeated measures, within-between interactions.
+    WithinBetweenRepeatedMANOVA {
+        /// Levels of between factor.
+        #[serde(deserialize_with = "deserialize_i64")]
+        k: i64,
+        /// Levels of repeated measures.
+        #[serde(deserialize_with = "deserialize_i64")]
+        m: i64,
+    },
+    /// Generic t test.
+    ///
+    /// The degrees of freedom and the noncentrality parameter are expressions in n and es;
+    /// without an expression, the noncentrality parameter is the effect size itself.
+    GenericT {
+        df: Expression,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ncp: Option<Expression>,
+    },
+    /// Generic F test, with expressions as for `GenericT`.
+    GenericF {
+        df1: Expression,
+        df2: Expression,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ncp: Option<Expression>,
+    },
+    /// Generic χ² test, with expressions as for `GenericT`.
+    GenericChisq {
+        df: Expression,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ncp: Option<Expression>,
+    },
+    /// Generic z test, where the noncentrality parameter is the mean of the statistic.
+    GenericZ {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ncp: Option<Expression>,
+    },
+}
+
+/// How the subjects of a test that compares two groups are divided over the groups.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Allocation {
+    /// Allocation ratio N2/N1.
+    #[serde(default = "equal_allocation", deserialize_with = "deserialize_f64")]
+    pub alloc_ratio: f64,
+    /// Size of group 1; only used for the post hoc and sensitivity analyses.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_option_f64",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub n1: Option<f64>,
+    /// Size of group 2; only used for the post hoc and sensitivity analyses.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_option_f64",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub n2: Option<f64>,
+}
+
+impl Default for Allocation {
+    fn default() -> Self {
+        Self {
+            alloc_ratio: equal_allocation(),
+            n1: None,
+            n2: None,
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
+impl Allocation {
+    /// Returns the group sizes, which are split from the total `n` by the allocation ratio
+    /// unless both are given.
+    fn group_sizes(&self, n: f64) -> (f64, f64) {
+        match (self.n1, self.n2) {
+            (Some(n1), Some(n2)) => (n1, n2),
+            _ => {
+                let n1 = n / (1.0 + self.alloc_ratio);
+                (n1, n - n1)
+            }
+        }
+    }
+
+    /// Returns the smallest total sample size with at least `size` subjects in each group.
+    fn min_n(&self, size: f64) -> f64 {
+        let ratio = self.alloc_ratio;
+        (size * (1.0 + ratio) / ratio.min(1.0)).ceil()
+    }
+}
+
+/// How the power of the test of two independent proportions is computed.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProportionsMethod {
+    /// Normal approximation with the pooled variance under the null hypothesis.
+    #[default]
+    ZTest,
+    /// Normal approximation with Yates' continuity correction.
+    ZTestCorrected,
+    /// Fisher's exact test, with power summed over all tables.
+    Fisher,
+    /// Normal approximation of the arcsine-transformed proportions; needs only h.
+    Arcsine,
+}
+
+/// How the power of the McNemar test is computed.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum McNemarMethod {
+    /// Binomial test on the discordant pairs, with power summed over their number.
+    #[default]
+    Exact,
+    /// Normal approximation of Connor (1987).
+    Asymptotic,
+}
+
+/// How the power of the test of a correlation against a constant is computed.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CorrelationMethod {
+    /// Exact distribution of r in a bivariate normal sample.
+    #[default]
+    Exact,
+    /// Normal approximation of Fisher's z = atanh r.
+    FisherZ,
+}
+
+/// Distribution of the tested predictor of a regression model.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PredictorDistribution {
+    #[default]
+    Normal,
+    /// A binary predictor.
+    Binomial,
+}
+
+/// How the power of the Wald test of logistic regression is computed.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LogisticMethod {
+    /// Variance of the estimate under both hypotheses from the information matrix
+    /// (Demidenko, 2007).
+    #[default]
+    Demidenko,
+    /// Closed-form approximations of Hsieh et al. (1998).
+    Hsieh,
+}
+
+/// How the number of events of the log-rank test translates into power.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SurvivalMethod {
+    /// Normal approximation of the log hazard ratio (Schoenfeld, 1981).
+    #[default]
+    Schoenfeld,
+    /// Normal approximation of the log-rank statistic (Freedman, 1982).
+    Freedman,
+}
+
+/// Parent distribution of the data of a rank test, which sets its efficiency relative to
+/// the t-test.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ParentDistribution {
+    /// ARE = 3/π.
+    #[default]
+    Normal,
+    /// ARE = 1.5.
+    Laplace,
+    /// ARE = π²/9.
+    Logistic,
+    /// The lower bound ARE = 0.864 over all continuous distributions (Hodges & Lehmann, 1956).
+    #[serde(rename = "minARE")]
+    MinARE,
+}
+
+impl ParentDistribution {
+    /// Asymptotic relative efficiency of the rank test to the t-test.
+    fn are(&self) -> f64 {
+        match self {
+            ParentDistribution::Normal => 3.0 / std::f64::consts::PI,
+            ParentDistribution::Laplace => 1.5,
+            ParentDistribution::Logistic => std::f64::consts::PI.powi(2) / 9.0,
+            ParentDistribution::MinARE => 0.864,
+        }
+    }
+}
+
+/// Hypotheses of an equivalence test.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EquivalenceMode {
+    /// The effect lies between the lower and upper margins.
+    #[default]
+    Equivalence,
+    /// The effect lies above the lower margin; for outcomes where lower is better, flip the
+    /// signs of the effect size and the margin.
+    NonInferiority,
+}
+
+/// Multivariate test statistic whose F approximation is used for MANOVA.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MultivariateStatistic {
+    /// Pillai's trace V.
+    #[default]
+    PillaiV,
+    /// Wilks' Λ, with Rao's F approximation.
+    WilksLambda,
+    /// Hotelling–Lawley trace T, with the F approximation of Pillai and Samson.
+    HotellingLawley,
+}
+
+/// Parameters of the test statistic, as reported by G*Power next to each analysis.
+#[derive(Debug, PartialEq)]
+pub struct Details {
+    /// Noncentrality parameter of the alternative distribution; exact tests have none.
+    pub ncp: Option<f64>,
+    /// Degrees of freedom of the test statistic.
+    pub df: Vec<f64>,
+    /// Critical value of the test statistic under the null hypothesis.
+    ///
+    /// For exact tests, this is the smallest count in the upper rejection region or, for a
+    /// one-sided test in the lower tail, the largest count in the lower one. Tests whose
+    /// rejection region depends on the margins of the table have none.
+    pub critical_value: Option<f64>,
+    /// Probability of the rejection region under the null hypothesis for exact tests.
+    pub actual_alpha: Option<f64>,
+    /// Expected number of discordant pairs for the McNemar test.
+    pub discordant_pairs: Option<f64>,
+    /// Expected number of events for survival tests.
+    pub events: Option<f64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
 pub enum Tail {
+    #[default]
     OneSided,
     TwoSided,
 }
 
-fn parse_i64(data: &Value, field: &str) -> Result<i64, String> {
-    let value = match data.get(field) {
-        Some(value) => value,
-        None => return Err(format!("Missing field: {}", field)),
+/// Side of the null value on which the sensitivity analysis looks for the effect size.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Direction {
+    /// Effects above the null value.
+    #[default]
+    Greater,
+    /// Effects below the null value, such as a variance ratio below 1 or ρ below ρ0.
+    Less,
+}
+
+fn equal_allocation() -> f64 {
+    1.0
+}
+
+/// Returns the smallest integer from `start` onwards, but not below `min`, where `achieved`
+/// reaches `power`; `achieved` is assumed to increase.
+fn smallest_reaching<F: Fn(f64) -> Result<f64, PowerError>>(
+    achieved: F,
+    start: f64,
+    min: f64,
+    power: f64,
+) -> Result<f64, PowerError> {
+    let mut x = start.ceil().max(min);
+    while x > min && achieved(x - 1.0)? >= power {
+        x -= 1.0;
+    }
+    while achieved(x)? < power {
+        x += 1.0;
+        if x > MAX_N {
+            return Err(PowerError::no_solution());
+        }
+    }
+    Ok(x)
+}
+
+/// Returns Cohen's h for the difference between the proportions `p1` and `p2`.
+fn cohens_h(p1: f64, p2: f64) -> f64 {
+    2.0 * p2.sqrt().asin() - 2.0 * p1.sqrt().asin()
+}
+
+/// Returns the proportion that differs from `p1` by Cohen's h.
+fn proportion_from_h(p1: f64, h: f64) -> f64 {
+    let angle = (p1.sqrt().asin() + h / 2.0).clamp(0.0, std::f64::consts::FRAC_PI_2);
+    angle.sin().powi(2)
+}
+
+/// Returns an error unless the allocation ratio N2/N1 is positive.
+fn check_allocation_ratio(alloc_ratio: f64) -> Result<(), PowerError> {
+    if alloc_ratio > 0.0 {
+        Ok(())
+    } else {
+        Err(PowerError::new(
+            ErrorCode::OutOfBounds,
+            Some("allocRatio"),
+            "the allocation ratio should be positive",
+        ))
+    }
+}
+
+/// Returns an error unless `rho` is a correlation strictly between -1 and 1.
+fn check_correlation(rho: f64, field: &str) -> Result<(), PowerError> {
+    if -1.0 < rho && rho < 1.0 {
+        Ok(())
+    } else {
+        Err(PowerError::new(
+            ErrorCode::OutOfBounds,
+            Some(field),
+            "the correlation should be between -1 and 1",
+        ))
+    }
+}
+
+/// Returns an error unless `p` is a probability strictly between 0 and 1.
+fn check_probability(p: f64, field: &str) -> Result<(), PowerError> {
+    if 0.0 < p && p < 1.0 {
+        Ok(())
+    } else {
+        Err(PowerError::new(
+            ErrorCode::OutOfBounds,
+            Some(field),
+            "the probability should be between 0 and 1",
+        ))
+    }
+}
+
+/// Returns an error unless the squared multiple correlation `r2` is at least 0 and below 1.
+fn check_r2(r2: f64, field: &str) -> Result<(), PowerError> {
+    if (0.0..1.0).contains(&r2) {
+        Ok(())
+    } else {
+        Err(PowerError::new(
+            ErrorCode::OutOfBounds,
+            Some(field),
+            "R² should be at least 0 and below 1",
+        ))
+    }
+}
+
+/// Returns an error with `message` unless `x` is positive.
+fn check_positive(x: f64, field: &str, message: &str) -> Result<(), PowerError> {
+    if x > 0.0 {
+        Ok(())
+    } else {
+        Err(PowerError::new(
+            ErrorCode::OutOfBounds,
+            Some(field),
+            message,
+        ))
+    }
+}
+
+/// Returns an error unless the given parameters of the predictor distribution are valid.
+fn check_predictor(sd: Option<f64>, prop: Option<f64>) -> Result<(), PowerError> {
+    if let Some(sd) = sd {
+        check_positive(sd, "xSd", "the standard deviation should be positive")?;
+    }
+    if let Some(prop) = prop {
+        check_probability(prop, "xProp")?;
+    }
+    Ok(())
+}
+
+/// Returns n times the variance of the slope estimate of a Poisson regression with unit
+/// rate at x = 0 and slope `beta` (Signorini, 1991).
+///
+/// With M the moment generating function of the predictor, this is M / (M M'' - M'²) at
+/// `beta`.
+fn poisson_slope_variance(
+    distribution: &PredictorDistribution,
+    mean: f64,
+    sd: f64,
+    prop: f64,
+    beta: f64,
+) -> f64 {
+    match distribution {
+        PredictorDistribution::Normal => {
+            (-beta * mean - beta * beta * sd * sd / 2.0).exp() / (sd * sd)
+        }
+        PredictorDistribution::Binomial => {
+            let m = 1.0 - prop + prop * beta.exp();
+            m / (prop * beta.exp() * (1.0 - prop))
+        }
+    }
+}
+
+/// Returns the probability of an event with exponential survival with hazard `hazard` when
+/// subjects enter uniformly over `accrual` and are followed for `follow_up` afterwards.
+fn event_probability(hazard: f64, accrual: f64, follow_up: f64) -> f64 {
+    let censored = if accrual > 0.0 {
+        ((-hazard * follow_up).exp() - (-hazard * (accrual + follow_up)).exp()) / (hazard * accrual)
+    } else {
+        (-hazard * follow_up).exp()
     };
-    let value: &str = value
-        .as_str()
-        .expect("{field} could not be converted to a str");
-    let value: i64 = value
-        .parse()
-        .expect("{field} could not be converted to an integer");
-    Ok(value)
-}
-
-fn parse_f64(data: &Value, field: &str) -> Result<f64, String> {
-    let value = match data.get(field) {
-        Some(value) => value,
-        None => return Err(format!("Missing field: {}", field)),
+    1.0 - censored
+}
+
+/// Returns the odds of the probability `p`.
+fn odds(p: f64) -> f64 {
+    p / (1.0 - p)
+}
+
+/// Returns the probability with the given log odds.
+fn logistic(log_odds: f64) -> f64 {
+    1.0 / (1.0 + (-log_odds).exp())
+}
+
+/// Number of Simpson intervals for expectations over a normal predictor.
+const PREDICTOR_INTERVALS: usize = 400;
+
+/// Returns the values and probability weights of a predictor with the given distribution,
+/// which is centered for a normal predictor; a normal predictor is discretized on ±10
+/// standard deviations for Simpson's rule.
+fn predictor_values(distribution: &PredictorDistribution, sd: f64, prop: f64) -> Vec<(f64, f64)> {
+    match distribution {
+        PredictorDistribution::Binomial => vec![(0.0, 1.0 - prop), (1.0, prop)],
+        PredictorDistribution::Normal => {
+            let h = 20.0 / PREDICTOR_INTERVALS as f64;
+            (0..=PREDICTOR_INTERVALS)
+                .map(|i| {
+                    let z = -10.0 + i as f64 * h;
+                    let simpson = match i {
+                        0 | PREDICTOR_INTERVALS => 1.0,
+                        i if i % 2 == 1 => 4.0,
+                        _ => 2.0,
+                    };
+                    let density = (-z * z / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt();
+                    (sd * z, simpson * h / 3.0 * density)
+                })
+                .collect()
+        }
+    }
+}
+
+/// Indices of the two variables of a correlation.
+type Pair = (usize, usize);
+
+/// Correlation matrix of the variables of a dependent correlations test and the pairs of
+/// variables whose correlations are compared.
+type CorrelationMatrix = (Vec<Vec<f64>>, Pair, Pair);
+
+/// Returns the correlation matrix of variables 1 to 3, where ρ13 differs from ρ12 by `es` on
+/// the Fisher z scale.
+fn common_index_matrix(rho12: f64, rho23: f64, es: f64) -> CorrelationMatrix {
+    let rho13 = (rho12.atanh() + es).tanh();
+    let r = vec![
+        vec![1.0, rho12, rho13],
+        vec![rho12, 1.0, rho23],
+        vec![rho13, rho23, 1.0],
+    ];
+    (r, (0, 1), (0, 2))
+}
+
+/// Returns the correlation matrix of variables 1 to 4 from ρ12, ρ13, ρ14, ρ23, and ρ24, where
+/// ρ34 differs from ρ12 by `es` on the Fisher z scale.
+fn no_common_index_matrix(
+    [rho12, rho13, rho14, rho23, rho24]: [f64; 5],
+    es: f64,
+) -> CorrelationMatrix {
+    let rho34 = (rho12.atanh() + es).tanh();
+    let r = vec![
+        vec![1.0, rho12, rho13, rho14],
+        vec![rho12, 1.0, rho23, rho24],
+        vec![rho13, rho23, 1.0, rho34],
+        vec![rho14, rho24, rho34, 1.0],
+    ];
+    (r, (0, 1), (2, 3))
+}
+
+/// Returns the distribution of the difference of the Fisher z transforms of two dependent
+/// correlations in units of its standard error under the null hypothesis.
+fn dependent_correlations_statistic<F: Fn(f64) -> CorrelationMatrix>(
+    n: f64,
+    es: f64,
+    matrix: F,
+) -> Normal {
+    // Variances of the difference of the two z's, times n - 3.
+    let variance = |es| {
+        let (r, first, second) = matrix(es);
+        (2.0 - 2.0 * z_covariance(&r, first, second)).max(0.0)
     };
-    let value: &str = value
-        .as_str()
-        .expect("{field} could not be converted to a str");
-    let value: f64 = value
-        .parse()
-        .expect("{field} could not be converted to a floating number");
-    Ok(value)
-}
-
-impl Tail {
-    pub fn from_json(data: &Value) -> Option<Tail> {
-        let tail: i64 = parse_i64(data, "tail").unwrap();
-        match tail {
-            1 => Some(Tail::OneSided),
-            2 => Some(Tail::TwoSided),
-            _ => None,
+    let (var0, var1) = (variance(0.0), variance(es));
+    let ncp = es.abs() * ((n - 3.0) / var0).sqrt();
+    Normal::new(ncp, (var1 / var0).sqrt())
+}
+
+/// Returns whether the symmetric matrix `r` is positive definite, by trying to compute its
+/// Cholesky decomposition.
+fn is_positive_definite(r: &[Vec<f64>]) -> bool {
+    let len = r.len();
+    let mut l = vec![vec![0.0; len]; len];
+    for i in 0..len {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| l[i][k] * l[j][k]).sum();
+            if i == j {
+                let diagonal = r[i][i] - sum;
+                if diagonal <= 0.0 {
+                    return false;
+                }
+                l[i][i] = diagonal.sqrt();
+            } else {
+                l[i][j] = (r[i][j] - sum) / l[j][j];
+            }
+        }
+    }
+    true
+}
+
+/// Returns n times the covariance of the Fisher z transforms of the sample correlations of
+/// the variables `(j, k)` and `(h, m)`, whose correlation matrix is `r`.
+///
+/// This is the asymptotic covariance of Pearson and Filon (1898) in the form of Steiger
+/// (1980), equation 2.
+fn z_covariance(r: &[Vec<f64>], (j, k): Pair, (h, m): Pair) -> f64 {
+    let psi = 0.5
+        * r[j][k]
+        * r[h][m]
+        * (r[j][h].powi(2) + r[j][m].powi(2) + r[k][h].powi(2) + r[k][m].powi(2))
+        + r[j][h] * r[k][m]
+        + r[j][m] * r[k][h]
+        - (r[j][k] * r[j][h] * r[j][m]
+            + r[k][j] * r[k][h] * r[k][m]
+            + r[h][j] * r[h][k] * r[h][m]
+            + r[m][j] * r[m][k] * r[m][h]);
+    psi / ((1.0 - r[j][k].powi(2)) * (1.0 - r[h][m].powi(2)))
+}
+
+/// Returns an error unless the degrees of freedom of a generic test are positive, at least
+/// for large samples.
+fn check_df(df: &Expression, field: &str) -> Result<(), PowerError> {
+    let large = df.eval(MAX_N, 0.0);
+    if large.is_nan() || large <= 0.0 {
+        return Err(PowerError::new(
+            ErrorCode::OutOfBounds,
+            Some(field),
+            "the degrees of freedom should be positive",
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the value of the noncentrality parameter of a generic test, which is the effect
+/// size unless an expression is given.
+fn generic_ncp(ncp: &Option<Expression>, n: f64, es: f64) -> f64 {
+    match ncp {
+        Some(ncp) => ncp.eval(n, es),
+        None => es,
+    }
+}
+
+/// Returns an error unless an equivalence test has an upper margin above the lower one.
+fn check_margins(lower: f64, upper: Option<f64>, mode: &EquivalenceMode) -> Result<(), PowerError> {
+    if *mode == EquivalenceMode::NonInferiority {
+        return Ok(());
+    }
+    match upper {
+        None => Err(PowerError::missing_field("upper")),
+        Some(upper) if upper <= lower => Err(PowerError::new(
+            ErrorCode::OutOfBounds,
+            Some("upper"),
+            "the upper margin should be above the lower margin",
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Returns an error unless there is at least one response variable.
+fn check_responses(n_responses: i64) -> Result<(), PowerError> {
+    if n_responses < 1 {
+        return Err(PowerError::new(
+            ErrorCode::OutOfBounds,
+            Some("nResponses"),
+            "the number of response variables should be at least 1",
+        ));
+    }
+    Ok(())
+}
+
+/// Returns an error unless there are at least `min` repeated measures.
+fn check_measurements(m: i64, min: i64) -> Result<(), PowerError> {
+    if m < min {
+        return Err(PowerError::new(
+            ErrorCode::OutOfBounds,
+            Some("m"),
+            &format!("the number of measurements should be at least {min}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns an error unless there are at least `min` groups.
+fn check_groups(k: i64, min: i64) -> Result<(), PowerError> {
+    if k < min {
+        return Err(PowerError::new(
+            ErrorCode::OutOfBounds,
+            Some("k"),
+            &format!("the number of groups should be at least {min}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns an error unless the sample size `size` is at least `min`, and whole for exact
+/// tests.
+fn check_sample_size(size: f64, min: f64, exact: bool, field: &str) -> Result<(), PowerError> {
+    if size.is_nan() || size < min {
+        return Err(PowerError::new(
+            ErrorCode::OutOfBounds,
+            Some(field),
+            &format!("the sample size should be at least {min}"),
+        ));
+    }
+    if exact && size.fract() != 0.0 {
+        return Err(PowerError::new(
+            ErrorCode::OutOfBounds,
+            Some(field),
+            "the sample size of an exact test should be a whole number",
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the F approximation of a multivariate test of an effect with `q` degrees of
+/// freedom on `p` responses with `error_df` error degrees of freedom and `n` subjects.
+///
+/// The noncentrality parameter is n·s·f² for the effect size f, with s the number of
+/// nonzero eigenvalues or, for Wilks' Λ, the exponent of Rao's approximation (O'Brien &
+/// Shieh, 1992). Using the total n instead of the error df follows O'Brien & Muller (1993).
+fn multivariate_f(
+    statistic: &MultivariateStatistic,
+    p: f64,
+    q: f64,
+    error_df: f64,
+    n: f64,
+    es: f64,
+) -> NoncentralF {
+    let s = p.min(q);
+    let (s, v2) = match statistic {
+        MultivariateStatistic::PillaiV => (s, s * (error_df - p + s)),
+        MultivariateStatistic::WilksLambda => {
+            let g = if p * p + q * q - 5.0 > 0.0 {
+                ((p * p * q * q - 4.0) / (p * p + q * q - 5.0)).sqrt()
+            } else {
+                1.0
+            };
+            (
+                g,
+                g * (error_df - (p - q + 1.0) / 2.0) - (p * q - 2.0) / 2.0,
+            )
+        }
+        MultivariateStatistic::HotellingLawley => (s, s * (error_df - p - 1.0) + 2.0),
+    };
+    NoncentralF::new(p * q, v2, es.powi(2) * n * s)
+}
+
+/// Returns an error if the nonsphericity correction is below its lower bound.
+fn check_epsilon(m: i64, epsilon: f64) -> Result<(), PowerError> {
+    if epsilon < (1.0 / (m as f64 - 1.0)) {
+        Err(PowerError::new(
+            ErrorCode::OutOfBounds,
+            Some("epsilon"),
+            "lower bound of ε corresponds to 1 / (number of measurements - 1)",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// The front end sends the tail as `"1"` or `"2"`.
+impl Serialize for Tail {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Tail::OneSided => serializer.serialize_str("1"),
+            Tail::TwoSided => serializer.serialize_str("2"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Tail {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match deserialize_i64(deserializer)? {
+            1 => Ok(Tail::OneSided),
+            2 => Ok(Tail::TwoSided),
+            tail => Err(de::Error::custom(format!(
+                "invalid value: `{tail}`, expected 1 or 2"
+            ))),
         }
     }
 }
 
 impl TestKind {
-    pub fn from_str(text: &str, data: &Value) -> Result<TestKind, String> {
-        match text {
-            "oneSampleTTest" => Ok(TestKind::OneSampleTTest),
-            "independentSamplesTTest" => Ok(TestKind::IndependentSamplesTTest),
-            "goodnessOfFitChisqTest" => {
-                let df = parse_i64(data, "df").unwrap();
-                Ok(TestKind::GoodnessOfFitChisqTest { df })
-            }
-            "deviationFromZeroMultipleRegression" => {
-                let n_predictors = parse_i64(data, "nPredictors").unwrap();
-                Ok(TestKind::DeviationFromZeroMultipleRegression { n_predictors })
-            }
-            "increaseMultipleRegression" => {
-                let p = parse_i64(data, "p").unwrap();
-                let q = parse_i64(data, "q").unwrap();
-                Ok(TestKind::IncreaseMultipleRegression { p, q })
-            }
-            "ANCOVA" => {
-                let k = parse_i64(data, "k").unwrap();
-                let q = parse_i64(data, "q").unwrap();
-                let p = parse_i64(data, "p").unwrap();
-                Ok(TestKind::ANCOVA { k, q, p })
-            }
-            "oneWayANOVA" => {
-                let k = parse_i64(data, "k").unwrap();
-                Ok(TestKind::OneWayANOVA { k })
-            }
-            "twoWayANOVA" => {
-                let k = parse_i64(data, "k").unwrap();
-                let q = parse_i64(data, "q").unwrap();
-                Ok(TestKind::TwoWayANOVA { k, q })
-            }
-            "betweenRepeatedANOVA" => {
-                let k = parse_i64(data, "k").unwrap();
-                let m = parse_i64(data, "m").unwrap();
-                let rho = parse_f64(data, "rho").unwrap();
-                Ok(TestKind::BetweenRepeatedANOVA { k, m, rho })
-            }
-            "withinRepeatedANOVA" => {
-                let k = parse_i64(data, "k").unwrap();
-                let m = parse_i64(data, "m").unwrap();
-                let rho = parse_f64(data, "rho").unwrap();
-                let epsilon = parse_f64(data, "epsilon").unwrap();
-                if epsilon < (1.0 / (m as f64 - 1.0)) {
-                    Err(
-                        "lower bound of ε corresponds to 1 / (number of measurements - 1)"
-                            .to_string(),
-                    )
-                } else {
-                    Ok(TestKind::WithinRepeatedANOVA { k, m, rho, epsilon })
+    /// Checks the constraints between parameters that deserialization cannot express.
+    pub fn validate(&self) -> Result<(), PowerError> {
+        if let Some(allocation) = self.allocation() {
+            check_allocation_ratio(allocation.alloc_ratio)?;
+        }
+        match self {
+            TestKind::ANCOVA { k, .. }
+            | TestKind::OneWayANOVA { k }
+            | TestKind::TwoWayANOVA { k, .. }
+            | TestKind::GlobalMANOVA { k, .. }
+            | TestKind::BetweenRepeatedANOVA { k, .. }
+            | TestKind::WithinBetweenRepeatedANOVA { k, .. }
+            | TestKind::BetweenRepeatedMANOVA { k, .. }
+            | TestKind::WithinBetweenRepeatedMANOVA { k, .. } => check_groups(*k, 2)?,
+            // The measurements of a single group can be compared.
+            TestKind::WithinRepeatedANOVA { k, .. } | TestKind::WithinRepeatedMANOVA { k, .. } => {
+                check_groups(*k, 1)?
+            }
+            _ => (),
+        }
+        match self {
+            TestKind::PairedSamplesTTest { .. } => self.derived_es().map(|_| ()),
+            TestKind::IndependentSamplesWelchTTest { .. } => self.variance_ratio().map(|_| ()),
+            TestKind::TwoProportionsTest { p1, method, .. } => {
+                if *method != ProportionsMethod::Arcsine && p1.is_none() {
+                    return Err(PowerError::missing_field("p1"));
                 }
+                self.derived_es().map(|_| ())
             }
-            "withinBetweenRepeatedANOVA" => {
-                let k = parse_i64(data, "k").unwrap();
-                let m = parse_i64(data, "m").unwrap();
-                let rho = parse_f64(data, "rho").unwrap();
-                let epsilon = parse_f64(data, "epsilon").unwrap();
-                if epsilon < (1.0 / (m as f64 - 1.0)) {
-                    Err(
-                        "lower bound of ε corresponds to 1 / (number of measurements - 1)"
-                            .to_string(),
-                    )
-                } else {
-                    Ok(TestKind::WithinBetweenRepeatedANOVA { k, m, rho, epsilon })
+            TestKind::McNemarTest { .. } => {
+                self.derived_es()?;
+                self.discordant_proportion().map(|_| ())
+            }
+            TestKind::CorrelationTest { rho0, .. } => check_correlation(*rho0, "rho0"),
+            TestKind::DependentCorrelationsCommonIndexTest { rho12, rho23, .. } => {
+                check_correlation(*rho12, "rho12")?;
+                check_correlation(*rho23, "rho23")?;
+                self.check_correlation_matrix()
+            }
+            TestKind::DependentCorrelationsNoCommonIndexTest {
+                rho12,
+                rho13,
+                rho14,
+                rho23,
+                rho24,
+                ..
+            } => {
+                check_correlation(*rho12, "rho12")?;
+                check_correlation(*rho13, "rho13")?;
+                check_correlation(*rho14, "rho14")?;
+                check_correlation(*rho23, "rho23")?;
+                check_correlation(*rho24, "rho24")?;
+                self.check_correlation_matrix()
+            }
+            TestKind::TwoCorrelationsTest { .. } => self.derived_es().map(|_| ()),
+            TestKind::LogisticRegression {
+                p0,
+                p1,
+                r2_other,
+                x_sd,
+                x_prop,
+                ..
+            } => {
+                check_probability(*p0, "p0")?;
+                if let Some(p1) = p1 {
+                    check_probability(*p1, "p1")?;
+                }
+                check_r2(*r2_other, "r2Other")?;
+                check_predictor(*x_sd, *x_prop)
+            }
+            TestKind::PoissonRegression {
+                base_rate,
+                exposure,
+                r2_other,
+                x_sd,
+                x_prop,
+                ..
+            } => {
+                check_positive(*base_rate, "baseRate", "the base rate should be positive")?;
+                if let Some(exposure) = exposure {
+                    check_positive(*exposure, "exposure", "the exposure should be positive")?;
                 }
+                check_r2(*r2_other, "r2Other")?;
+                check_predictor(*x_sd, *x_prop)
+            }
+            TestKind::SurvivalTest { .. } => self.event_probabilities(1.0).map(|_| ()),
+            TestKind::OneSampleEquivalenceTest { lower, upper, mode }
+            | TestKind::PairedSamplesEquivalenceTest { lower, upper, mode }
+            | TestKind::IndependentSamplesEquivalenceTest {
+                lower, upper, mode, ..
+            } => check_margins(*lower, *upper, mode),
+            TestKind::OneSampleHotellingT2Test { n_responses }
+            | TestKind::TwoSampleHotellingT2Test { n_responses, .. }
+            | TestKind::GlobalMANOVA { n_responses, .. } => check_responses(*n_responses),
+            TestKind::GenericT { df, .. } | TestKind::GenericChisq { df, .. } => check_df(df, "df"),
+            TestKind::GenericF { df1, df2, .. } => {
+                check_df(df1, "df1")?;
+                check_df(df2, "df2")
+            }
+            TestKind::ExactBinomialTest { p0 } if !(0.0 < *p0 && *p0 < 1.0) => {
+                Err(PowerError::new(
+                    ErrorCode::OutOfBounds,
+                    Some("p0"),
+                    "the proportion should be between 0 and 1",
+                ))
             }
-            _ => Err(format!("Unknown test: {}", text)),
+            TestKind::WithinRepeatedANOVA { m, epsilon, .. }
+            | TestKind::WithinBetweenRepeatedANOVA { m, epsilon, .. } => {
+                check_measurements(*m, 2)?;
+                check_epsilon(*m, *epsilon)
+            }
+            TestKind::WithinRepeatedMANOVA { m, .. }
+            | TestKind::WithinBetweenRepeatedMANOVA { m, .. } => check_measurements(*m, 2),
+            // The between factor may be tested on a single measurement.
+            TestKind::BetweenRepeatedANOVA { m, .. }
+            | TestKind::BetweenRepeatedMANOVA { m, .. } => check_measurements(*m, 1),
+            _ => Ok(()),
         }
     }
 
-    fn alternative_distribution(&self, n: f64, es: f64) -> Dist {
+    /// Returns the effect size that follows from the design parameters, if they determine one.
+    fn derived_es(&self) -> Result<Option<f64>, PowerError> {
         match self {
-            TestKind::OneSampleTTest => Box::new(NoncentralT::new(n - 1.0, n.sqrt() * es)),
-            TestKind::IndependentSamplesTTest => {
-                let v = n - 2.0; // n1 + n2 - 2
-                Box::new(NoncentralT::new(v, (n / 2.0).sqrt() * es))
+            TestKind::PairedSamplesTTest {
+                mean1,
+                mean2,
+                sd1,
+                sd2,
+                rho,
+            } => {
+                if mean1.is_none() && mean2.is_none() {
+                    return Ok(None);
+                }
+                let required = |value: &Option<f64>, field: &str| {
+                    value.ok_or_else(|| PowerError::missing_field(field))
+                };
+                let (mean1, mean2) = (required(mean1, "mean1")?, required(mean2, "mean2")?);
+                let (sd1, sd2) = (required(sd1, "sd1")?, required(sd2, "sd2")?);
+                let rho = required(rho, "rho")?;
+                if !(-1.0..1.0).contains(&rho) {
+                    return Err(PowerError::new(
+                        ErrorCode::OutOfBounds,
+                        Some("rho"),
+                        "the correlation should be at least -1 and below 1",
+                    ));
+                }
+                let sd_diff = (sd1.powi(2) + sd2.powi(2) - 2.0 * rho * sd1 * sd2).sqrt();
+                if sd1 <= 0.0 || sd2 <= 0.0 || sd_diff <= 0.0 {
+                    return Err(PowerError::new(
+                        ErrorCode::OutOfBounds,
+                        Some("sd1"),
+                        "the standard deviations should be positive",
+                    ));
+                }
+                Ok(Some((mean1 - mean2).abs() / sd_diff))
+            }
+            TestKind::TwoProportionsTest { p1, p2, .. } => {
+                let check = |p: &Option<f64>, field: &str| match p {
+                    Some(p) if !(0.0..=1.0).contains(p) => Err(PowerError::new(
+                        ErrorCode::OutOfBounds,
+                        Some(field),
+                        "the proportion should be between 0 and 1",
+                    )),
+                    _ => Ok(()),
+                };
+                check(p1, "p1")?;
+                check(p2, "p2")?;
+                match (p1, p2) {
+                    (_, None) => Ok(None),
+                    (None, Some(_)) => Err(PowerError::missing_field("p1")),
+                    (Some(p1), Some(p2)) => Ok(Some(cohens_h(*p1, *p2))),
+                }
+            }
+            TestKind::McNemarTest { p12, p21, .. } => match (p12, p21) {
+                (None, None) => Ok(None),
+                (Some(_), None) => Err(PowerError::missing_field("p21")),
+                (None, Some(_)) => Err(PowerError::missing_field("p12")),
+                (Some(p12), Some(p21)) if *p12 <= 0.0 || *p21 <= 0.0 => Err(PowerError::new(
+                    ErrorCode::OutOfBounds,
+                    Some(if *p12 <= 0.0 { "p12" } else { "p21" }),
+                    "the discordant proportions should be positive",
+                )),
+                (Some(p12), Some(p21)) => Ok(Some(p12 / p21)),
+            },
+            TestKind::TwoCorrelationsTest { rho1, rho2, .. } => match (rho1, rho2) {
+                (None, None) => Ok(None),
+                (Some(_), None) => Err(PowerError::missing_field("rho2")),
+                (None, Some(_)) => Err(PowerError::missing_field("rho1")),
+                (Some(rho1), Some(rho2)) => {
+                    check_correlation(*rho1, "rho1")?;
+                    check_correlation(*rho2, "rho2")?;
+                    Ok(Some(rho2.atanh() - rho1.atanh()))
+                }
+            },
+            TestKind::LogisticRegression {
+                p0, p1: Some(p1), ..
+            } => Ok(Some(odds(*p1) / odds(*p0))),
+            TestKind::DependentCorrelationsCommonIndexTest {
+                rho12,
+                rho13: Some(rho),
+                ..
+            } => {
+                check_correlation(*rho, "rho13")?;
+                Ok(Some(rho.atanh() - rho12.atanh()))
+            }
+            TestKind::DependentCorrelationsNoCommonIndexTest {
+                rho12,
+                rho34: Some(rho),
+                ..
+            } => {
+                check_correlation(*rho, "rho34")?;
+                Ok(Some(rho.atanh() - rho12.atanh()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the effect size derived from the design parameters or, if there is none, `es`.
+    pub fn effect_size(&self, es: Option<f64>) -> Result<f64, PowerError> {
+        match (self.derived_es()?, es) {
+            (Some(derived), _) => Ok(derived),
+            (None, Some(es)) => Ok(es),
+            (None, None) => Err(PowerError::missing_field("es")),
+        }
+    }
+
+    /// Returns the variance ratio σ2²/σ1² of the Welch test.
+    fn variance_ratio(&self) -> Result<f64, PowerError> {
+        let TestKind::IndependentSamplesWelchTTest {
+            sd1,
+            sd2,
+            var_ratio,
+            ..
+        } = self
+        else {
+            return Ok(1.0);
+        };
+        let (ratio, field) = match (var_ratio, sd1, sd2) {
+            (Some(ratio), _, _) => (*ratio, "varRatio"),
+            (None, Some(sd1), Some(sd2)) if *sd1 > 0.0 => ((sd2 / sd1).powi(2), "sd2"),
+            (None, Some(_), Some(_)) => (0.0, "sd1"),
+            (None, Some(_), None) => return Err(PowerError::missing_field("sd2")),
+            (None, None, Some(_)) => return Err(PowerError::missing_field("sd1")),
+            (None, None, None) => return Err(PowerError::missing_field("varRatio")),
+        };
+        if ratio > 0.0 && ratio.is_finite() {
+            Ok(ratio)
+        } else {
+            Err(PowerError::new(
+                ErrorCode::OutOfBounds,
+                Some(field),
+                "the variances should be positive",
+            ))
+        }
+    }
+
+    /// Returns the proportion of discordant pairs of the McNemar test.
+    fn discordant_proportion(&self) -> Result<f64, PowerError> {
+        let TestKind::McNemarTest {
+            prop_discordant,
+            p12,
+            p21,
+            ..
+        } = self
+        else {
+            return Ok(0.0);
+        };
+        let (proportion, field) = match (p12, p21, prop_discordant) {
+            (Some(p12), Some(p21), _) => (p12 + p21, "p12"),
+            (_, _, Some(proportion)) => (*proportion, "propDiscordant"),
+            _ => return Err(PowerError::missing_field("propDiscordant")),
+        };
+        if 0.0 < proportion && proportion <= 1.0 {
+            Ok(proportion)
+        } else {
+            Err(PowerError::new(
+                ErrorCode::OutOfBounds,
+                Some(field),
+                "the proportion of discordant pairs should be between 0 and 1",
+            ))
+        }
+    }
+
+    /// Returns the probabilities of an event in group 1 and group 2 of a survival test with
+    /// hazard ratio `es`.
+    fn event_probabilities(&self, es: f64) -> Result<(f64, f64), PowerError> {
+        let TestKind::SurvivalTest {
+            median1,
+            accrual,
+            follow_up,
+            event_prob1,
+            event_prob2,
+            ..
+        } = self
+        else {
+            return Ok((0.0, 0.0));
+        };
+        let check = |p: f64, field: &str| {
+            if 0.0 < p && p <= 1.0 {
+                Ok(p)
+            } else {
+                Err(PowerError::new(
+                    ErrorCode::OutOfBounds,
+                    Some(field),
+                    "the event probability should be above 0 and at most 1",
+                ))
+            }
+        };
+        match (event_prob1, event_prob2) {
+            (Some(p1), Some(p2)) => {
+                return Ok((check(*p1, "eventProb1")?, check(*p2, "eventProb2")?))
+            }
+            (Some(_), None) => return Err(PowerError::missing_field("eventProb2")),
+            (None, Some(_)) => return Err(PowerError::missing_field("eventProb1")),
+            (None, None) => {}
+        }
+        let median1 = median1.ok_or_else(|| PowerError::missing_field("median1"))?;
+        check_positive(median1, "median1", "the median survival should be positive")?;
+        let accrual = accrual.unwrap_or(0.0);
+        let follow_up = follow_up.ok_or_else(|| PowerError::missing_field("followUp"))?;
+        if accrual < 0.0 || follow_up < 0.0 || accrual + follow_up <= 0.0 {
+            return Err(PowerError::new(
+                ErrorCode::OutOfBounds,
+                Some(if accrual < 0.0 { "accrual" } else { "followUp" }),
+                "the accrual and follow-up times should add up to a positive study length",
+            ));
+        }
+        let hazard1 = std::f64::consts::LN_2 / median1;
+        Ok((
+            event_probability(hazard1, accrual, follow_up),
+            event_probability(hazard1 * es, accrual, follow_up),
+        ))
+    }
+
+    /// Returns the expected number of events out of `n` subjects for survival tests.
+    fn expected_events(&self, n: f64, es: f64) -> Result<Option<f64>, PowerError> {
+        let TestKind::SurvivalTest { allocation, .. } = self else {
+            return Ok(None);
+        };
+        let (n1, n2) = allocation.group_sizes(n);
+        let (p1, p2) = self.event_probabilities(es)?;
+        Ok(Some(n1 * p1 + n2 * p2))
+    }
+
+    /// Returns the expected number of discordant pairs out of `n` for the McNemar test.
+    fn discordant_pairs(&self, n: f64) -> Result<Option<f64>, PowerError> {
+        match self {
+            TestKind::McNemarTest { .. } => Ok(Some(n * self.discordant_proportion()?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the correlation matrix of the variables of a dependent correlations test, with
+    /// the tested correlation differing from ρ12 by `es` on the Fisher z scale, and the pairs
+    /// of variables whose correlations are compared.
+    fn correlation_matrix(&self, es: f64) -> Option<CorrelationMatrix> {
+        match self {
+            TestKind::DependentCorrelationsCommonIndexTest { rho12, rho23, .. } => {
+                Some(common_index_matrix(*rho12, *rho23, es))
+            }
+            TestKind::DependentCorrelationsNoCommonIndexTest {
+                rho12,
+                rho13,
+                rho14,
+                rho23,
+                rho24,
+                ..
+            } => Some(no_common_index_matrix(
+                [*rho12, *rho13, *rho14, *rho23, *rho24],
+                es,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Returns an error unless the correlations of a dependent correlations test are
+    /// consistent under both hypotheses.
+    fn check_correlation_matrix(&self) -> Result<(), PowerError> {
+        let es = self.derived_es()?.unwrap_or(0.0);
+        for es in [0.0, es] {
+            let Some((r, _, _)) = self.correlation_matrix(es) else {
+                return Ok(());
+            };
+            if !is_positive_definite(&r) {
+                return Err(PowerError::new(
+                    ErrorCode::OutOfBounds,
+                    None,
+                    "the correlations should form a positive definite correlation matrix",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the effect size under the null hypothesis.
+    fn null_es(&self) -> f64 {
+        match self {
+            _ if self.is_ratio() => 1.0,
+            TestKind::CorrelationTest { rho0, .. } => *rho0,
+            TestKind::OneSampleEquivalenceTest { lower, .. }
+            | TestKind::PairedSamplesEquivalenceTest { lower, .. }
+            | TestKind::IndependentSamplesEquivalenceTest { lower, .. } => *lower,
+            _ => 0.0,
+        }
+    }
+
+    /// Returns whether the power is defined for effects below the null value, which are then
+    /// tested in the lower tail; for the other tests, only the size of the effect matters or
+    /// a one-sided test only rejects above the null value.
+    fn has_lower_effects(&self) -> bool {
+        self.is_ratio()
+            || matches!(
+                self,
+                TestKind::CorrelationTest { .. }
+                    | TestKind::ExactBinomialTest { .. }
+                    | TestKind::TwoProportionsTest { .. }
+            )
+    }
+
+    /// Returns whether the effect size is a ratio, which is 1 under the null hypothesis.
+    fn is_ratio(&self) -> bool {
+        matches!(
+            self,
+            // The odds ratio.
+            TestKind::McNemarTest { .. }
+                | TestKind::LogisticRegression { .. }
+                // The variance ratios.
+                | TestKind::VarianceTest
+                | TestKind::TwoVariancesTest { .. }
+                // The rate ratio and the hazard ratio.
+                | TestKind::PoissonRegression { .. }
+                | TestKind::SurvivalTest { .. }
+        )
+    }
+
+    /// Returns an error unless the effect size is positive for tests where it is a ratio, or
+    /// gives a proportion strictly between 0 and 1 for the exact binomial test.
+    fn check_es(&self, es: f64) -> Result<(), PowerError> {
+        if self.is_ratio() {
+            check_positive(es, "es", "the ratio should be positive")?;
+        }
+        if let TestKind::ExactBinomialTest { p0 } = self {
+            let p1 = p0 + es;
+            if !(0.0 < p1 && p1 < 1.0) {
+                return Err(PowerError::new(
+                    ErrorCode::OutOfBounds,
+                    Some("es"),
+                    "p0 + es should be a proportion between 0 and 1",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns an error unless the sample size `n`, or the group sizes if both are given,
+    /// are large enough for the test and whole for exact tests.
+    fn check_n(&self, n: f64) -> Result<(), PowerError> {
+        let exact = self.is_exact();
+        if let Some(Allocation {
+            n1: Some(n1),
+            n2: Some(n2),
+            ..
+        }) = self.allocation()
+        {
+            let min = self.min_group_size();
+            check_sample_size(*n1, min, exact, "n1")?;
+            check_sample_size(*n2, min, exact, "n2")?;
+            // Either group may be the one to enlarge, so the error names the total.
+            let min_n = self.min_n();
+            if n1 + n2 < min_n {
+                return Err(PowerError::new(
+                    ErrorCode::OutOfBounds,
+                    Some("n"),
+                    &format!("the total of n1 and n2 should be at least {min_n}"),
+                ));
+            }
+            return Ok(());
+        }
+        check_sample_size(n, self.min_n(), exact, "n")
+    }
+
+    /// Returns the allocation of tests that compare two groups.
+    pub fn allocation(&self) -> Option<&Allocation> {
+        match self {
+            TestKind::IndependentSamplesTTest { allocation }
+            | TestKind::IndependentSamplesWelchTTest { allocation, .. }
+            | TestKind::TwoProportionsTest { allocation, .. }
+            | TestKind::TwoCorrelationsTest { allocation, .. }
+            | TestKind::SurvivalTest { allocation, .. }
+            | TestKind::TwoSampleHotellingT2Test { allocation, .. }
+            | TestKind::IndependentSamplesEquivalenceTest { allocation, .. }
+            | TestKind::MannWhitneyTest { allocation, .. }
+            | TestKind::TwoVariancesTest { allocation, .. } => Some(allocation),
+            _ => None,
+        }
+    }
+
+    fn allocation_mut(&mut self) -> Option<&mut Allocation> {
+        match self {
+            TestKind::IndependentSamplesTTest { allocation }
+            | TestKind::IndependentSamplesWelchTTest { allocation, .. }
+            | TestKind::TwoProportionsTest { allocation, .. }
+            | TestKind::TwoCorrelationsTest { allocation, .. }
+            | TestKind::SurvivalTest { allocation, .. }
+            | TestKind::TwoSampleHotellingT2Test { allocation, .. }
+            | TestKind::IndependentSamplesEquivalenceTest { allocation, .. }
+            | TestKind::MannWhitneyTest { allocation, .. }
+            | TestKind::TwoVariancesTest { allocation, .. } => Some(allocation),
+            _ => None,
+        }
+    }
+
+    /// Returns the allocation ratio N2/N1 for tests that compare two groups.
+    fn allocation_ratio(&self) -> Option<f64> {
+        self.allocation().map(|allocation| allocation.alloc_ratio)
+    }
+
+    /// Returns a copy of a two-group test with the given group sizes.
+    pub fn with_group_sizes(&self, n1: f64, n2: f64) -> TestKind {
+        let mut test = self.clone();
+        if let Some(allocation) = test.allocation_mut() {
+            (allocation.n1, allocation.n2) = (Some(n1), Some(n2));
+        }
+        test
+    }
+
+    /// Smallest size of each group of a two-group test.
+    fn min_group_size(&self) -> f64 {
+        match self {
+            // Both groups need two subjects to estimate their variance.
+            TestKind::IndependentSamplesWelchTTest { .. } | TestKind::TwoVariancesTest { .. } => {
+                2.0
+            }
+            // Fisher's z has variance 1 / (n - 3).
+            TestKind::TwoCorrelationsTest { .. } => 4.0,
+            _ => 1.0,
+        }
+    }
+
+    /// Smallest sample size for which the test has at least one error degree of freedom.
+    fn min_n(&self) -> f64 {
+        let min_n = match self {
+            TestKind::OneSampleTTest
+            | TestKind::PairedSamplesTTest { .. }
+            | TestKind::OneSampleEquivalenceTest { .. }
+            | TestKind::PairedSamplesEquivalenceTest { .. }
+            | TestKind::WilcoxonSignedRankTest { .. }
+            | TestKind::WilcoxonSignedRankPairedTest { .. } => 2,
+            TestKind::IndependentSamplesTTest { .. }
+            | TestKind::IndependentSamplesEquivalenceTest { .. }
+            | TestKind::MannWhitneyTest { .. } => 3,
+            TestKind::IndependentSamplesWelchTTest { allocation, .. }
+            | TestKind::TwoVariancesTest { allocation } => {
+                return allocation.min_n(self.min_group_size());
+            }
+            TestKind::VarianceTest => 2,
+            TestKind::ExactBinomialTest { .. } => 1,
+            TestKind::TwoProportionsTest { .. } => 2,
+            TestKind::McNemarTest { .. } | TestKind::SurvivalTest { .. } => 2,
+            // Fisher's z has variance 1 / (n - 3).
+            TestKind::CorrelationTest { .. }
+            | TestKind::DependentCorrelationsCommonIndexTest { .. }
+            | TestKind::DependentCorrelationsNoCommonIndexTest { .. } => 4,
+            TestKind::TwoCorrelationsTest { allocation, .. } => {
+                return allocation.min_n(self.min_group_size());
+            }
+            TestKind::OneSampleHotellingT2Test { n_responses } => n_responses + 1,
+            TestKind::TwoSampleHotellingT2Test { n_responses, .. } => n_responses + 2,
+            // The Hotelling–Lawley approximation needs an error df above #responses.
+            TestKind::GlobalMANOVA {
+                k,
+                n_responses,
+                statistic: MultivariateStatistic::HotellingLawley,
+            } => k + n_responses + 1,
+            TestKind::GlobalMANOVA { k, n_responses, .. } => k + n_responses,
+            TestKind::GoodnessOfFitChisqTest { .. } => 2,
+            TestKind::DeviationFromZeroMultipleRegression { n_predictors } => n_predictors + 2,
+            TestKind::IncreaseMultipleRegression { p, .. } => p + 2,
+            TestKind::LogisticRegression { .. } | TestKind::PoissonRegression { .. } => 2,
+            TestKind::ANCOVA { k, p, .. } => k + p + 2,
+            TestKind::OneWayANOVA { k }
+            | TestKind::TwoWayANOVA { k, .. }
+            | TestKind::BetweenRepeatedANOVA { k, .. }
+            | TestKind::WithinRepeatedANOVA { k, .. }
+            | TestKind::WithinBetweenRepeatedANOVA { k, .. }
+            | TestKind::BetweenRepeatedMANOVA { k, .. } => k + 1,
+            TestKind::WithinRepeatedMANOVA { k, m }
+            | TestKind::WithinBetweenRepeatedMANOVA { k, m } => k + m - 1,
+            // The smallest sample size that leaves positive degrees of freedom, found by
+            // bisection since the degrees of freedom grow with n.
+            TestKind::GenericT { .. }
+            | TestKind::GenericF { .. }
+            | TestKind::GenericChisq { .. }
+            | TestKind::GenericZ { .. } => {
+                let positive = |n| self.generic_df(n).iter().all(|df| *df > 0.0);
+                if positive(1.0) {
+                    return 1.0;
+                }
+                let (mut lower, mut upper) = (1.0, MAX_N);
+                while upper - lower > 1.0 {
+                    let mid = ((lower + upper) / 2.0_f64).floor();
+                    if positive(mid) {
+                        upper = mid;
+                    } else {
+                        lower = mid;
+                    }
+                }
+                return upper;
+            }
+        };
+        min_n as f64
+    }
+
+    fn alternative_distribution(&self, n: f64, es: f64) -> Result<Dist, PowerError> {
+        let dist: Dist = match self {
+            TestKind::OneSampleTTest | TestKind::PairedSamplesTTest { .. } => {
+                Box::new(NoncentralT::new(n - 1.0, n.sqrt() * es))
+            }
+            TestKind::IndependentSamplesTTest { allocation } => {
+                let (n1, n2) = allocation.group_sizes(n);
+                let v = n1 + n2 - 2.0;
+                Box::new(NoncentralT::new(v, (n1 * n2 / (n1 + n2)).sqrt() * es))
+            }
+            TestKind::IndependentSamplesWelchTTest { allocation, .. } => {
+                let (n1, n2) = allocation.group_sizes(n);
+                let ratio = self.variance_ratio()?;
+                // Variances in units of the mean variance (σ1² + σ2²) / 2.
+                let (var1, var2) = (2.0 / (1.0 + ratio), 2.0 * ratio / (1.0 + ratio));
+                let (se1, se2) = (var1 / n1, var2 / n2);
+                // Welch–Satterthwaite approximation.
+                let v = (se1 + se2).powi(2) / (se1.powi(2) / (n1 - 1.0) + se2.powi(2) / (n2 - 1.0));
+                Box::new(NoncentralT::new(v, es / (se1 + se2).sqrt()))
+            }
+            // (n - 1) s² / σ0² is σ²/σ0² times a chi-squared variable.
+            TestKind::VarianceTest => Box::new(Scaled::new(
+                Box::new(NoncentralChisq::new(n - 1.0, 0.0)),
+                es,
+            )),
+            // s2² / s1² is σ2²/σ1² times an F variable.
+            TestKind::TwoVariancesTest { allocation } => {
+                let (n1, n2) = allocation.group_sizes(n);
+                Box::new(Scaled::new(
+                    Box::new(NoncentralF::new(n2 - 1.0, n1 - 1.0, 0.0)),
+                    es,
+                ))
+            }
+            TestKind::WilcoxonSignedRankTest { parent }
+            | TestKind::WilcoxonSignedRankPairedTest { parent } => {
+                let n = n * parent.are();
+                Box::new(NoncentralT::new(n - 1.0, n.sqrt() * es))
+            }
+            TestKind::MannWhitneyTest {
+                parent, allocation, ..
+            } => {
+                let (n1, n2) = allocation.group_sizes(n);
+                let (n1, n2) = (n1 * parent.are(), n2 * parent.are());
+                let v = n1 + n2 - 2.0;
+                Box::new(NoncentralT::new(v, (n1 * n2 / (n1 + n2)).sqrt() * es))
+            }
+            // The statistic of the test against the lower margin.
+            TestKind::OneSampleEquivalenceTest { lower, .. }
+            | TestKind::PairedSamplesEquivalenceTest { lower, .. }
+            | TestKind::IndependentSamplesEquivalenceTest { lower, .. } => {
+                let (v, m) = self.t_sample_sizes(n);
+                Box::new(NoncentralT::new(v, m.sqrt() * (es - lower)))
             }
             TestKind::DeviationFromZeroMultipleRegression { n_predictors } => {
                 Box::new(NoncentralF::new(
@@ -222,9 +1995,172 @@ impl TestKind {
                     es.powi(2) * n,
                 ))
             }
+            TestKind::TwoProportionsTest {
+                p1,
+                method,
+                allocation,
+                ..
+            } => {
+                let (n1, n2) = allocation.group_sizes(n);
+                if *method == ProportionsMethod::Arcsine {
+                    let ncp = (n1 * n2 / (n1 + n2)).sqrt() * es.abs();
+                    return Ok(Box::new(Normal::new(ncp, 1.0)));
+                }
+                let p1 = p1.ok_or_else(|| PowerError::missing_field("p1"))?;
+                let p2 = proportion_from_h(p1, es);
+                let pooled = (n1 * p1 + n2 * p2) / (n1 + n2);
+                let sd0 = (pooled * (1.0 - pooled) * (1.0 / n1 + 1.0 / n2)).sqrt();
+                let sd1 = (p1 * (1.0 - p1) / n1 + p2 * (1.0 - p2) / n2).sqrt();
+                let correction = match method {
+                    ProportionsMethod::ZTestCorrected => (1.0 / n1 + 1.0 / n2) / 2.0,
+                    _ => 0.0,
+                };
+                // The statistic in units of its standard error under the null hypothesis.
+                let difference = ((p2 - p1).abs() - correction).max(0.0);
+                Box::new(Normal::new(difference / sd0, sd1 / sd0))
+            }
+            TestKind::McNemarTest { .. } => {
+                let discordant = self.discordant_proportion()?;
+                // Difference p12 - p21 of the discordant proportions.
+                let difference = discordant * (es - 1.0) / (es + 1.0);
+                let sd = ((discordant - difference.powi(2)) / discordant).sqrt();
+                Box::new(Normal::new((n / discordant).sqrt() * difference.abs(), sd))
+            }
+            TestKind::CorrelationTest { rho0, method } => {
+                let rho = es.clamp(-MAX_CORRELATION, MAX_CORRELATION);
+                match method {
+                    CorrelationMethod::FisherZ => {
+                        let ncp = (rho.atanh() - rho0.atanh()) * (n - 3.0).sqrt();
+                        Box::new(Normal::new(ncp.abs(), 1.0))
+                    }
+                    CorrelationMethod::Exact if self.is_mirrored(es) => {
+                        Box::new(SampleCorrelation::new(n, -rho))
+                    }
+                    CorrelationMethod::Exact => Box::new(SampleCorrelation::new(n, rho)),
+                }
+            }
+            TestKind::TwoCorrelationsTest { allocation, .. } => {
+                let (n1, n2) = allocation.group_sizes(n);
+                let se = (1.0 / (n1 - 3.0) + 1.0 / (n2 - 3.0)).sqrt();
+                Box::new(Normal::new(es.abs() / se, 1.0))
+            }
+            TestKind::DependentCorrelationsCommonIndexTest { rho12, rho23, .. } => {
+                Box::new(dependent_correlations_statistic(n, es, |es| {
+                    common_index_matrix(*rho12, *rho23, es)
+                }))
+            }
+            TestKind::DependentCorrelationsNoCommonIndexTest {
+                rho12,
+                rho13,
+                rho14,
+                rho23,
+                rho24,
+                ..
+            } => {
+                let rhos = [*rho12, *rho13, *rho14, *rho23, *rho24];
+                Box::new(dependent_correlations_statistic(n, es, |es| {
+                    no_common_index_matrix(rhos, es)
+                }))
+            }
+            TestKind::SurvivalTest {
+                method, allocation, ..
+            } => {
+                let (n1, n2) = allocation.group_sizes(n);
+                let (p1, p2) = self.event_probabilities(es)?;
+                let events = n1 * p1 + n2 * p2;
+                let ncp = match method {
+                    SurvivalMethod::Schoenfeld => {
+                        es.ln().abs() * (events * n1 * n2).sqrt() / (n1 + n2)
+                    }
+                    SurvivalMethod::Freedman => {
+                        let ratio = n2 / n1;
+                        (events * ratio).sqrt() * (1.0 - es).abs() / (1.0 + ratio * es)
+                    }
+                };
+                Box::new(Normal::new(ncp, 1.0))
+            }
+            // Its power is summed over the rejection region by `exact_probability`.
+            TestKind::ExactBinomialTest { .. } => {
+                return Err(PowerError::new(
+                    ErrorCode::InvalidValue,
+                    Some("test"),
+                    "the exact binomial test has no continuous test statistic",
+                ))
+            }
             TestKind::GoodnessOfFitChisqTest { df } => {
                 Box::new(NoncentralChisq::new(*df as f64, es.powi(2) * n))
             }
+            TestKind::LogisticRegression {
+                p0,
+                r2_other,
+                x_distribution,
+                x_sd,
+                x_prop,
+                method,
+                ..
+            } => {
+                let (sd, prop) = (x_sd.unwrap_or(1.0), x_prop.unwrap_or(0.5));
+                let beta = es.ln();
+                let effective_n = n * (1.0 - r2_other);
+                // The Wald statistic in units of its standard error under the null hypothesis.
+                let (mean, sd) = match (method, x_distribution) {
+                    (LogisticMethod::Hsieh, PredictorDistribution::Normal) => {
+                        let mean = beta.abs() * sd * (effective_n * p0 * (1.0 - p0)).sqrt();
+                        (mean, 1.0)
+                    }
+                    (LogisticMethod::Hsieh, PredictorDistribution::Binomial) => {
+                        let p1 = logistic(odds(*p0).ln() + beta);
+                        let pooled = (1.0 - prop) * p0 + prop * p1;
+                        let sd0 = (pooled * (1.0 - pooled) / prop).sqrt();
+                        let sd1 = (p0 * (1.0 - p0) + p1 * (1.0 - p1) * (1.0 - prop) / prop).sqrt();
+                        let mean = (p1 - p0).abs() * (effective_n * (1.0 - prop)).sqrt() / sd0;
+                        (mean, sd1 / sd0)
+                    }
+                    (LogisticMethod::Demidenko, _) => {
+                        let values = predictor_values(x_distribution, sd, prop);
+                        // Information matrix of the intercept and the slope, the mean event
+                        // probability, and the mean and variance of the predictor.
+                        let (mut i00, mut i01, mut i11) = (0.0, 0.0, 0.0);
+                        let (mut pooled, mut mean_x, mut mean_x2) = (0.0, 0.0, 0.0);
+                        for (x, weight) in values {
+                            let p = logistic(odds(*p0).ln() + beta * x);
+                            let w = weight * p * (1.0 - p);
+                            (i00, i01, i11) = (i00 + w, i01 + w * x, i11 + w * x * x);
+                            pooled += weight * p;
+                            (mean_x, mean_x2) = (mean_x + weight * x, mean_x2 + weight * x * x);
+                        }
+                        let var1 = i00 / (i00 * i11 - i01 * i01);
+                        let var0 = 1.0 / (pooled * (1.0 - pooled) * (mean_x2 - mean_x * mean_x));
+                        (
+                            beta.abs() * (effective_n / var0).sqrt(),
+                            (var1 / var0).sqrt(),
+                        )
+                    }
+                };
+                Box::new(Normal::new(mean, sd))
+            }
+            TestKind::PoissonRegression {
+                base_rate,
+                exposure,
+                r2_other,
+                x_distribution,
+                x_mean,
+                x_sd,
+                x_prop,
+            } => {
+                let variance = |beta| {
+                    let (mean, sd) = (x_mean.unwrap_or(0.0), x_sd.unwrap_or(1.0));
+                    poisson_slope_variance(x_distribution, mean, sd, x_prop.unwrap_or(0.5), beta)
+                };
+                let beta = es.ln();
+                let (var0, var1) = (variance(0.0), variance(beta));
+                // Expected number of events at x = 0, which scales the information.
+                let events = n * (1.0 - r2_other) * exposure.unwrap_or(1.0) * base_rate;
+                Box::new(Normal::new(
+                    beta.abs() * (events / var0).sqrt(),
+                    (var1 / var0).sqrt(),
+                ))
+            }
             TestKind::IncreaseMultipleRegression { p, q } => Box::new(NoncentralF::new(
                 *q as f64,
                 n - (*p as f64) - 1.0,
@@ -243,6 +2179,35 @@ impl TestKind {
             TestKind::TwoWayANOVA { k, q } => {
                 Box::new(NoncentralF::new(*q as f64, n - *k as f64, es.powi(2) * n))
             }
+            TestKind::OneSampleHotellingT2Test { n_responses } => {
+                let p = *n_responses as f64;
+                Box::new(NoncentralF::new(p, n - p, es.powi(2) * n))
+            }
+            TestKind::TwoSampleHotellingT2Test {
+                n_responses,
+                allocation,
+                ..
+            } => {
+                let (n1, n2) = allocation.group_sizes(n);
+                let p = *n_responses as f64;
+                Box::new(NoncentralF::new(
+                    p,
+                    n1 + n2 - p - 1.0,
+                    es.powi(2) * n1 * n2 / (n1 + n2),
+                ))
+            }
+            TestKind::GlobalMANOVA {
+                k,
+                n_responses,
+                statistic,
+            } => Box::new(multivariate_f(
+                statistic,
+                *n_responses as f64,
+                *k as f64 - 1.0,
+                n - *k as f64,
+                n,
+                es,
+            )),
 
             TestKind::BetweenRepeatedANOVA { k, m, rho } => {
                 let u = *m as f64 / (1.0 + ((*m as f64 - 1.0) * *rho));
@@ -268,62 +2233,519 @@ impl TestKind {
                     es.powi(2) * u * n * *epsilon,
                 ))
             }
+            TestKind::BetweenRepeatedMANOVA { k, .. } => Box::new(multivariate_f(
+                &MultivariateStatistic::PillaiV,
+                1.0,
+                *k as f64 - 1.0,
+                n - *k as f64,
+                n,
+                es,
+            )),
+            TestKind::WithinRepeatedMANOVA { k, m } => Box::new(multivariate_f(
+                &MultivariateStatistic::PillaiV,
+                *m as f64 - 1.0,
+                1.0,
+                n - *k as f64,
+                n,
+                es,
+            )),
+            TestKind::GenericT { df, ncp } => {
+                Box::new(NoncentralT::new(df.eval(n, es), generic_ncp(ncp, n, es)))
+            }
+            TestKind::GenericF { df1, df2, ncp } => Box::new(NoncentralF::new(
+                df1.eval(n, es),
+                df2.eval(n, es),
+                generic_ncp(ncp, n, es),
+            )),
+            TestKind::GenericChisq { df, ncp } => Box::new(NoncentralChisq::new(
+                df.eval(n, es),
+                generic_ncp(ncp, n, es),
+            )),
+            TestKind::GenericZ { ncp } => Box::new(Normal::new(generic_ncp(ncp, n, es), 1.0)),
+            TestKind::WithinBetweenRepeatedMANOVA { k, m } => Box::new(multivariate_f(
+                &MultivariateStatistic::PillaiV,
+                *m as f64 - 1.0,
+                *k as f64 - 1.0,
+                n - *k as f64,
+                n,
+                es,
+            )),
+        };
+        Ok(dist)
+    }
+
+    fn null_distribution(&self, n: f64, es: f64) -> Result<Dist, PowerError> {
+        match self {
+            // The null hypothesis is ρ = ρ0, not independence.
+            TestKind::CorrelationTest {
+                rho0,
+                method: CorrelationMethod::Exact,
+            } => {
+                let rho0 = if self.is_mirrored(es) { -rho0 } else { *rho0 };
+                Ok(Box::new(SampleCorrelation::new(n, rho0)))
+            }
+            _ => Ok(self.alternative_distribution(n, es)?.central_distribution()),
+        }
+    }
+
+    /// Returns whether the exact correlation test works with -r so that a one-sided test in
+    /// the lower tail becomes one in the upper tail, which `power` assumes.
+    fn is_mirrored(&self, es: f64) -> bool {
+        match self {
+            TestKind::CorrelationTest {
+                rho0,
+                method: CorrelationMethod::Exact,
+            } => es < *rho0,
+            _ => false,
+        }
+    }
+
+    /// Returns the degrees of freedom of a generic test at sample size `n`; expressions for
+    /// them that use the effect size see es = 0.
+    fn generic_df(&self, n: f64) -> Vec<f64> {
+        let eval = |df: &Expression| df.eval(n, 0.0);
+        match self {
+            TestKind::GenericT { df, .. } | TestKind::GenericChisq { df, .. } => vec![eval(df)],
+            TestKind::GenericF { df1, df2, .. } => vec![eval(df1), eval(df2)],
+            _ => vec![],
         }
     }
 
-    fn null_distribution(&self, n: f64, es: f64) -> Dist {
-        self.alternative_distribution(n, es).central_distribution()
+    /// Returns whether a variance test rejects in the lower tail, which it does for a ratio
+    /// below 1.
+    fn is_lower_tailed(&self, es: f64) -> bool {
+        matches!(
+            self,
+            TestKind::VarianceTest | TestKind::TwoVariancesTest { .. }
+        ) && es < 1.0
+    }
+
+    /// Returns the lower margin and, for equivalence rather than non-inferiority, the upper
+    /// margin of an equivalence test.
+    fn equivalence_margins(&self) -> Option<(f64, Option<f64>)> {
+        match self {
+            TestKind::OneSampleEquivalenceTest { lower, upper, mode }
+            | TestKind::PairedSamplesEquivalenceTest { lower, upper, mode }
+            | TestKind::IndependentSamplesEquivalenceTest {
+                lower, upper, mode, ..
+            } => match mode {
+                EquivalenceMode::Equivalence => Some((*lower, *upper)),
+                EquivalenceMode::NonInferiority => Some((*lower, None)),
+            },
+            _ => None,
+        }
     }
 
-    pub fn n(&self, tail: Tail, alpha: f64, power: f64, es: f64) -> i64 {
-        let f = |n| self.alpha(tail.clone(), n, power, es) - alpha;
-        let mut conv = SimpleConvergency {
-            eps: 0.0001f64,
-            max_iter: 500,
+    /// Returns the degrees of freedom and the effective sample size of the t statistic of an
+    /// equivalence test, whose noncentrality is the effect size times the root of the latter.
+    ///
+    /// Those of the one-sample and paired tests are returned for tests of one sample.
+    fn t_sample_sizes(&self, n: f64) -> (f64, f64) {
+        match self {
+            TestKind::IndependentSamplesEquivalenceTest { allocation, .. } => {
+                let (n1, n2) = allocation.group_sizes(n);
+                (n1 + n2 - 2.0, n1 * n2 / (n1 + n2))
+            }
+            _ => (n - 1.0, n),
+        }
+    }
+
+    /// Returns the tail of the test; equivalence tests are one-sided against each margin.
+    fn tail_of(&self, tail: Tail) -> Tail {
+        match self.equivalence_margins() {
+            Some(_) => Tail::OneSided,
+            None => tail,
+        }
+    }
+
+    fn is_exact(&self) -> bool {
+        matches!(
+            self,
+            TestKind::ExactBinomialTest { .. }
+                | TestKind::TwoProportionsTest {
+                    method: ProportionsMethod::Fisher,
+                    ..
+                }
+                | TestKind::McNemarTest {
+                    method: McNemarMethod::Exact,
+                    ..
+                }
+        )
+    }
+
+    /// Returns the rejection region if the test is exact.
+    fn exact_region(&self, tail: &Tail, n: f64, alpha: f64, es: f64) -> Option<Region> {
+        match self {
+            TestKind::ExactBinomialTest { p0 } => {
+                Some(Region::binomial(n, *p0, tail, alpha, es >= 0.0))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the probability of the rejection region of an exact test when the true
+    /// effect size is `true_es`; this is the power for `es` and the actual α for the effect
+    /// size under the null hypothesis.
+    fn exact_probability(
+        &self,
+        tail: &Tail,
+        n: f64,
+        alpha: f64,
+        es: f64,
+        true_es: f64,
+    ) -> Result<Option<f64>, PowerError> {
+        match self {
+            TestKind::ExactBinomialTest { p0 } => Ok(self
+                .exact_region(tail, n, alpha, es)
+                .map(|region| region.binomial_probability(n, p0 + true_es))),
+            TestKind::TwoProportionsTest {
+                p1: Some(p1),
+                method: ProportionsMethod::Fisher,
+                allocation,
+                ..
+            } => {
+                let (n1, n2) = allocation.group_sizes(n);
+                let p2 = proportion_from_h(*p1, true_es);
+                Ok(Some(fisher_probability(
+                    n1,
+                    n2,
+                    *p1,
+                    p2,
+                    tail,
+                    alpha,
+                    es >= 0.0,
+                )))
+            }
+            TestKind::McNemarTest {
+                method: McNemarMethod::Exact,
+                ..
+            } => {
+                let discordant = self.discordant_proportion()?;
+                // Probability that a discordant pair has a success in the first measurement.
+                let p12 = true_es / (1.0 + true_es);
+                Ok(Some(mcnemar_probability(
+                    n,
+                    discordant,
+                    p12,
+                    tail,
+                    alpha,
+                    es >= 1.0,
+                )))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the test with `k` subjects and its total sample size.
+    ///
+    /// For two-group tests, `k` is the size of group 1 and the size of group 2 follows from
+    /// the allocation ratio, rounded up. Repeated measures ANOVAs round `k` up to a multiple
+    /// of the number of groups, so that the groups are of equal size as in G*Power.
+    fn design(&self, k: f64) -> (TestKind, f64) {
+        match (self, self.allocation_ratio()) {
+            (_, Some(ratio)) => {
+                let n2 = (ratio * k - 1e-9).ceil().max(1.0);
+                (self.with_group_sizes(k, n2), k + n2)
+            }
+            (
+                TestKind::BetweenRepeatedANOVA { k: groups, .. }
+                | TestKind::WithinRepeatedANOVA { k: groups, .. }
+                | TestKind::WithinBetweenRepeatedANOVA { k: groups, .. },
+                None,
+            ) => {
+                let groups = *groups as f64;
+                (self.clone(), (k / groups).ceil() * groups)
+            }
+            (_, None) => (self.clone(), k),
+        }
+    }
+
+    /// Returns the smallest sample size that reaches the target power and the test at that
+    /// size.
+    ///
+    /// For two-group tests, this is the total of the smallest group 1 size and the group 2
+    /// size that follows from the allocation ratio, rounded up, and the returned test has
+    /// these group sizes.
+    pub fn n(
+        &self,
+        tail: Tail,
+        alpha: f64,
+        power: f64,
+        es: f64,
+    ) -> Result<(TestKind, i64), PowerError> {
+        self.validate()?;
+        check_probability(alpha, "alpha")?;
+        check_probability(power, "power")?;
+        self.check_es(es)?;
+        if let Some(allocation) = self.allocation() {
+            // The group sizes are what the analysis solves for.
+            let given = [("n1", allocation.n1), ("n2", allocation.n2)];
+            if let Some((field, _)) = given.iter().find(|(_, size)| size.is_some()) {
+                return Err(PowerError::new(
+                    ErrorCode::InvalidValue,
+                    Some(field),
+                    "the group sizes cannot be given when solving for n; use allocRatio",
+                ));
+            }
+        }
+        let achieved = |k: f64| {
+            let (test, n) = self.design(k);
+            test.power_at(&tail, n, alpha, es)
+        };
+        let min_n = self.min_n();
+        let (min_k, share) = match self.allocation_ratio() {
+            None => (min_n, 1.0),
+            Some(ratio) => ((min_n / (1.0 + ratio)).ceil().max(2.0), 1.0 / (1.0 + ratio)),
         };
-        let step_size = 20;
-        // There is probably a better way to do this, but it works.
-        for lower in (0..1000).step_by(step_size) {
-            let upper = lower + step_size;
-            let root = find_root_regula_falsi(lower as f64, upper as f64, f, &mut conv);
-            let n = root.unwrap_or(-111.0);
-            if n == -111.0 || n.is_nan() {
-                continue;
+        let k = if self.is_exact() {
+            // Power is not monotone in n for discrete tests and may already reach the target
+            // below a size that falls short, so the doubling only shows that some size
+            // reaches it and every size from the smallest one up is scanned.
+            let mut upper = min_k;
+            while achieved(upper)? < power {
+                upper *= 2.0;
+                if upper > MAX_N {
+                    return Err(PowerError::no_solution());
+                }
+            }
+            let mut k = min_k;
+            while k < upper && achieved(k)? < power {
+                k += 1.0;
             }
-            return n.ceil() as i64;
+            k
+        } else if achieved(min_k)? >= power {
+            // The power has no root above the smallest sample size, which already suffices.
+            min_k
+        } else {
+            // The power only fails for invalid parameters, which were rejected above, and the
+            // root finder skips NaN.
+            let f = |n| {
+                self.power_at(&tail, n, alpha, es)
+                    .map_or(f64::NAN, |p| p - power)
+            };
+            let root = find_root(f, min_n, MAX_N).ok_or_else(PowerError::no_solution)?;
+            // The root is continuous, so check the neighbouring integers.
+            smallest_reaching(achieved, root * share, min_k, power)?
+        };
+        let (test, n) = self.design(k);
+        Ok((test, n as i64))
+    }
+
+    pub fn alpha(&self, tail: Tail, n: f64, power: f64, es: f64) -> Result<f64, PowerError> {
+        self.validate()?;
+        self.check_n(n)?;
+        check_probability(power, "power")?;
+        self.check_es(es)?;
+        let tail = self.tail_of(tail);
+        if self.is_exact() || matches!(self.equivalence_margins(), Some((_, Some(_)))) {
+            // Power is a step function of α, so bisect for the smallest nominal α that
+            // reaches the target and report the actual α of its rejection region. The
+            // power of both one-sided tests has no closed-form inverse either.
+            let (mut lower, mut upper) = (0.0, 1.0);
+            for _ in 0..60 {
+                let mid = (lower + upper) / 2.0;
+                if self.power_at(&tail, n, mid, es)? >= power {
+                    upper = mid;
+                } else {
+                    lower = mid;
+                }
+            }
+            return Ok(self.actual_alpha(&tail, n, upper, es)?.unwrap_or(upper));
         }
-        -111
+        let d0 = self.null_distribution(n, es)?;
+        let d1 = self.alternative_distribution(n, es)?;
+        let upper = !self.is_lower_tailed(es);
+        let critical_value = d1.quantile(power, !upper);
+        let one_tail = d0.cdf(critical_value, !upper);
+        Ok(match tail {
+            Tail::OneSided => one_tail,
+            Tail::TwoSided => 2.0 * one_tail,
+        })
     }
 
-    pub fn alpha(&self, tail: Tail, n: f64, power: f64, es: f64) -> f64 {
-        let d0 = self.null_distribution(n, es);
-        let d1 = self.alternative_distribution(n, es);
-        let critical_value = d1.quantile(power, false);
-        let right_tail = d0.cdf(critical_value, false);
-        match tail {
-            Tail::OneSided => right_tail,
-            Tail::TwoSided => 2.0 * right_tail,
+    /// Returns the α of the compromise analysis, at which β/α equals `ratio`.
+    ///
+    /// β/α decreases with α, so bisect for the smallest nominal α at which β is at most
+    /// `ratio`·α. For exact tests, β/α jumps between rejection regions, so the actual α of
+    /// the region at that α is reported.
+    pub fn compromise(&self, tail: Tail, n: f64, es: f64, ratio: f64) -> Result<f64, PowerError> {
+        self.validate()?;
+        self.check_n(n)?;
+        self.check_es(es)?;
+        if !(ratio > 0.0 && ratio.is_finite()) {
+            return Err(PowerError::new(
+                ErrorCode::OutOfBounds,
+                Some("betaAlphaRatio"),
+                "the ratio β/α should be positive",
+            ));
+        }
+        let tail = self.tail_of(tail);
+        let (mut lower, mut upper) = (0.0, 1.0);
+        for _ in 0..60 {
+            let mid = (lower + upper) / 2.0;
+            let beta = 1.0 - self.power_at(&tail, n, mid, es)?;
+            if beta <= ratio * mid {
+                upper = mid;
+            } else {
+                lower = mid;
+            }
         }
+        Ok(self.actual_alpha(&tail, n, upper, es)?.unwrap_or(upper))
     }
 
-    pub fn power(&self, tail: Tail, n: f64, alpha: f64, es: f64) -> f64 {
-        let d0 = self.null_distribution(n, es);
-        let d1 = self.alternative_distribution(n, es);
-        let right_tail = match tail {
+    /// Returns the critical value in the tail where the effect is expected; that is the
+    /// upper tail unless `is_lower_tailed`.
+    fn critical_value(&self, tail: &Tail, n: f64, alpha: f64, es: f64) -> Result<f64, PowerError> {
+        let d0 = self.null_distribution(n, es)?;
+        let one_tail = match tail {
             Tail::OneSided => alpha,
             Tail::TwoSided => alpha / 2.0,
         };
-        let critical_value = d0.quantile(right_tail, false);
-        d1.cdf(critical_value, false)
+        Ok(d0.quantile(one_tail, self.is_lower_tailed(es)))
     }
 
-    pub fn es(&self, tail: Tail, n: f64, alpha: f64, power: f64) -> f64 {
-        let f = |es| self.alpha(tail.clone(), n, power, es) - alpha;
-        let mut conv = SimpleConvergency {
-            eps: 0.0001f64,
-            max_iter: 500,
+    /// Returns the probability of the rejection region under the null hypothesis for exact
+    /// tests, which is at most `alpha`.
+    fn actual_alpha(
+        &self,
+        tail: &Tail,
+        n: f64,
+        alpha: f64,
+        es: f64,
+    ) -> Result<Option<f64>, PowerError> {
+        self.exact_probability(tail, n, alpha, es, self.null_es())
+    }
+
+    pub fn power(&self, tail: Tail, n: f64, alpha: f64, es: f64) -> Result<f64, PowerError> {
+        self.validate()?;
+        self.check_n(n)?;
+        check_probability(alpha, "alpha")?;
+        self.check_es(es)?;
+        self.power_at(&self.tail_of(tail), n, alpha, es)
+    }
+
+    /// Returns the power of a validated test; `tail` is that of the test, see `tail_of`.
+    fn power_at(&self, tail: &Tail, n: f64, alpha: f64, es: f64) -> Result<f64, PowerError> {
+        if let Some(power) = self.exact_probability(tail, n, alpha, es, es)? {
+            return Ok(power);
+        }
+        let d1 = self.alternative_distribution(n, es)?;
+        let critical_value = self.critical_value(tail, n, alpha, es)?;
+        if let Some((_, Some(upper))) = self.equivalence_margins() {
+            // Both statistics share the estimated standard deviation.
+            let (v, m) = self.t_sample_sizes(n);
+            let ncp_upper = m.sqrt() * (es - upper);
+            return Ok(ptost(critical_value, v, d1.ncp(), ncp_upper).max(0.0));
+        }
+        Ok(match tail {
+            Tail::OneSided if self.is_lower_tailed(es) => d1.cdf(critical_value, true),
+            Tail::OneSided => d1.cdf(critical_value, false),
+            Tail::TwoSided => {
+                let (lower_critical_value, upper_critical_value) = match self {
+                    // The null distributions of r unless ρ0 = 0 and of variances are skewed.
+                    TestKind::CorrelationTest {
+                        method: CorrelationMethod::Exact,
+                        ..
+                    }
+                    | TestKind::VarianceTest
+                    | TestKind::TwoVariancesTest { .. } => {
+                        let d0 = self.null_distribution(n, es)?;
+                        (
+                            d0.quantile(alpha / 2.0, true),
+                            d0.quantile(alpha / 2.0, false),
+                        )
+                    }
+                    // The lower rejection region is empty for the F and chi-squared
+                    // distributions.
+                    _ => (-critical_value, critical_value),
+                };
+                d1.cdf(upper_critical_value, false) + d1.cdf(lower_critical_value, true)
+            }
+        })
+    }
+
+    /// Returns the parameters of the test statistic for a fully specified design.
+    pub fn details(&self, tail: Tail, n: f64, alpha: f64, es: f64) -> Result<Details, PowerError> {
+        self.validate()?;
+        self.check_n(n)?;
+        self.check_es(es)?;
+        let tail = self.tail_of(tail);
+        if self.is_exact() {
+            let region = self.exact_region(&tail, n, alpha, es);
+            return Ok(Details {
+                ncp: None,
+                df: vec![],
+                critical_value: region.map(|region| match tail {
+                    Tail::OneSided if es < 0.0 => region.lower,
+                    _ => region.upper,
+                }),
+                actual_alpha: self.actual_alpha(&tail, n, alpha, es)?,
+                discordant_pairs: self.discordant_pairs(n)?,
+                events: None,
+            });
+        }
+        let d1 = self.alternative_distribution(n, es)?;
+        // Undo the mirroring, so that r and its critical value keep their sign.
+        let sign = if self.is_mirrored(es) { -1.0 } else { 1.0 };
+        Ok(Details {
+            // Both one-sided tests of equivalence have their own noncentrality.
+            ncp: match self.equivalence_margins() {
+                Some((_, Some(_))) => None,
+                _ => Some(sign * d1.ncp()),
+            },
+            df: d1.df(),
+            critical_value: Some(sign * self.critical_value(&tail, n, alpha, es)?),
+            actual_alpha: None,
+            discordant_pairs: self.discordant_pairs(n)?,
+            events: self.expected_events(n, es)?,
+        })
+    }
+
+    /// Returns the effect size that reaches the target power on the side of the null value
+    /// given by `direction`.
+    pub fn es(
+        &self,
+        tail: Tail,
+        direction: Direction,
+        n: f64,
+        alpha: f64,
+        power: f64,
+    ) -> Result<f64, PowerError> {
+        self.validate()?;
+        self.check_n(n)?;
+        check_probability(alpha, "alpha")?;
+        check_probability(power, "power")?;
+        if direction == Direction::Less && !self.has_lower_effects() {
+            return Err(PowerError::new(
+                ErrorCode::InvalidValue,
+                Some("direction"),
+                "the test has no effects below its null value",
+            ));
+        }
+        let tail = self.tail_of(tail);
+        // The power only fails for invalid parameters, which were rejected above, and the
+        // root finder skips NaN.
+        let f = |es| {
+            self.power_at(&tail, n, alpha, es)
+                .map_or(f64::NAN, |p| p - power)
+        };
+        if let Some((lower, Some(upper))) = self.equivalence_margins() {
+            // Power peaks halfway between the margins and falls towards either margin, so
+            // return the largest effect size that still reaches the target.
+            let middle = (lower + upper) / 2.0;
+            return find_root_between(f, middle, upper).ok_or_else(PowerError::no_solution);
+        }
+        // Effect sizes are searched at a distance d from the null value, which is not zero
+        // for ratios, ρ0 or a non-inferiority margin.
+        let null_es = self.null_es();
+        let effect = |d: f64| match direction {
+            Direction::Greater => null_es + d,
+            // Ratios below 1 mirror those above it on the log scale.
+            Direction::Less if self.is_ratio() => null_es / (1.0 + d),
+            Direction::Less => null_es - d,
         };
-        let root = find_root_regula_falsi(0.001f64, 8f64, f, &mut conv);
-        root.unwrap_or(-111.0)
+        find_root(|d| f(effect(d)), MIN_ES, MAX_ES)
+            .map(effect)
+            .ok_or_else(PowerError::no_solution)
     }
 }
@@ -0,0 +1,68 @@
+///
+/// Root finding for the analyses that solve for a parameter.
+///
+use roots::find_root_brent;
+use roots::SimpleConvergency;
+
+/// Returns an interval `[lower, upper]` over which `f` changes sign.
+///
+/// Starting at `[start, 2 * start]`, the interval is moved up by doubling its bounds
+/// until the sign changes or the upper bound exceeds `limit`.
+/// Points where `f` is not finite, such as sample sizes that leave no degrees of freedom,
+/// are skipped.
+pub fn bracket<F: Fn(f64) -> f64>(f: F, start: f64, limit: f64) -> Option<(f64, f64)> {
+    let mut lower = start;
+    let mut f_lower = f(lower);
+    let mut upper = 2.0 * start;
+    while upper <= limit {
+        let f_upper = f(upper);
+        if f_lower.is_finite() && f_upper.is_finite() && f_lower.signum() != f_upper.signum() {
+            return Some((lower, upper));
+        }
+        lower = upper;
+        f_lower = f_upper;
+        upper *= 2.0;
+    }
+    None
+}
+
+/// Returns the smallest root of `f` above `start`, or `None` if there is none below `limit`.
+pub fn find_root<F: Fn(f64) -> f64>(f: F, start: f64, limit: f64) -> Option<f64> {
+    let (lower, upper) = bracket(&f, start, limit)?;
+    find_root_between(f, lower, upper)
+}
+
+/// Returns a root of `f` in `[lower, upper]`, over which `f` changes sign.
+pub fn find_root_between<F: Fn(f64) -> f64>(f: F, lower: f64, upper: f64) -> Option<f64> {
+    let mut conv = SimpleConvergency {
+        eps: 1e-6f64,
+        max_iter: 500,
+    };
+    find_root_brent(lower, upper, &f, &mut conv)
+        .ok()
+        .filter(|root| root.is_finite())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn expands_until_sign_change() {
+        let f = |x: f64| x - 30_000.5;
+        assert_eq!(bracket(f, 1.0, 1e7), Some((16384.0, 32768.0)));
+        assert_relative_eq!(find_root(f, 1.0, 1e7).unwrap(), 30_000.5, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn skips_undefined_points() {
+        let f = |x: f64| if x < 10.0 { f64::NAN } else { x - 20.0 };
+        assert_relative_eq!(find_root(f, 1.0, 1e7).unwrap(), 20.0, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn no_root() {
+        assert_eq!(find_root(|x: f64| x + 1.0, 1.0, 1e7), None);
+    }
+}
@@ -2,51 +2,95 @@
 /// Logic to transfer strings between Javascript and WebAssembly.
 /// Thanks to Richard L. Apodaca at https://depth-first.com.
 ///
+/// The host allocates a buffer of exactly the request size with `alloc`, writes the UTF-8
+/// request into it, and passes the pointer and length to the exported function. Responses are
+/// returned as a pointer to a new buffer that starts with the length of the text as a
+/// little-endian `u32`, followed by the text. Both buffers are freed with `dealloc`.
+///
+use crate::error::ErrorCode;
+use crate::error::PowerError;
 use serde_json::Value;
-use std::ffi::{CStr, CString};
-use std::mem;
-use std::os::raw::c_void;
 
-#[no_mangle]
-pub extern "C" fn alloc() -> *mut c_void {
-    let mut buf = Vec::with_capacity(1024);
-    let ptr = buf.as_mut_ptr();
+/// Number of bytes in front of a response that hold its length.
+pub const LENGTH_PREFIX: usize = 4;
 
-    mem::forget(buf);
+fn into_raw(bytes: Vec<u8>) -> *mut u8 {
+    Box::into_raw(bytes.into_boxed_slice()) as *mut u8
+}
 
-    ptr
+#[no_mangle]
+pub extern "C" fn alloc(len: usize) -> *mut u8 {
+    into_raw(vec![0; len])
 }
 
+/// # Safety
+///
+/// `ptr` must have been returned by `alloc(len)` or by an exported function returning a
+/// response of `len` bytes including the length prefix, and must not be used afterwards.
 #[no_mangle]
-pub unsafe extern "C" fn dealloc(ptr: *mut c_void) {
-    Vec::from_raw_parts(ptr, 0, 1024);
+pub unsafe extern "C" fn dealloc(ptr: *mut u8, len: usize) {
+    drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, len)));
 }
 
-pub unsafe fn u8_to_string(ptr: *mut u8) -> String {
-    let mut text = CStr::from_ptr(ptr as *const i8)
-        .to_str()
-        .unwrap()
-        .to_string();
-    match text.find("<END>") {
-        Some(index) => text.truncate(index),
-        None => panic!("Expected <END> but couldn't find it."),
+/// # Safety
+///
+/// `ptr` must point to `len` initialized bytes.
+pub unsafe fn read_from_ptr(ptr: *const u8, len: usize) -> Result<String, PowerError> {
+    let bytes = std::slice::from_raw_parts(ptr, len);
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Ok(text.to_string()),
+        Err(_) => Err(PowerError::new(
+            ErrorCode::InvalidJson,
+            None,
+            "Input is not valid UTF-8",
+        )),
     }
-    text
 }
 
-pub fn write_to_ptr(ptr: *mut u8, text: &str) {
-    let c_headers = CString::new(text).unwrap();
-    let bytes = c_headers.as_bytes_with_nul();
-    let header_bytes = unsafe { std::slice::from_raw_parts_mut(ptr, 1024) };
-    header_bytes[..bytes.len()].copy_from_slice(bytes);
+/// Returns a new length-prefixed buffer holding `text`.
+pub fn write_to_new_ptr(text: &str) -> *mut u8 {
+    let bytes = text.as_bytes();
+    let length = u32::try_from(bytes.len()).expect("response should be smaller than 4 GiB");
+    let mut buf = Vec::with_capacity(LENGTH_PREFIX + bytes.len());
+    buf.extend_from_slice(&length.to_le_bytes());
+    buf.extend_from_slice(bytes);
+    into_raw(buf)
 }
 
-pub fn json(text: &str) -> Option<Value> {
-    match serde_json::from_str(text) {
-        Ok(parsed) => Some(parsed),
-        Err(error) => {
-            eprintln!("Failed to parse json: {error:?}");
-            None
+/// Parses `text`; the error says why and where parsing failed.
+pub fn json(text: &str) -> Result<Value, serde_json::Error> {
+    serde_json::from_str(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_without_size_limit() {
+        let text = "é".repeat(10_000);
+        let ptr = alloc(text.len());
+        unsafe {
+            std::ptr::copy_nonoverlapping(text.as_ptr(), ptr, text.len());
+            assert_eq!(read_from_ptr(ptr, text.len()).unwrap(), text);
+            dealloc(ptr, text.len());
+        }
+
+        let ptr = write_to_new_ptr(&text);
+        unsafe {
+            let prefix = std::slice::from_raw_parts(ptr, LENGTH_PREFIX);
+            let length = u32::from_le_bytes(prefix.try_into().unwrap()) as usize;
+            assert_eq!(length, text.len());
+            let returned = read_from_ptr(ptr.add(LENGTH_PREFIX), length).unwrap();
+            assert_eq!(returned, text);
+            dealloc(ptr, LENGTH_PREFIX + length);
         }
     }
+
+    #[test]
+    fn invalid_utf8() {
+        let bytes = [0xff, 0xfe];
+        let error = unsafe { read_from_ptr(bytes.as_ptr(), bytes.len()) }.unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidJson);
+    }
 }
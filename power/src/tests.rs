@@ -1,4 +1,18 @@
+use crate::error::PowerError;
+use crate::interface::calculatePower;
 use crate::interface::handle_received;
+use crate::interface::Analysis;
+use crate::interface::Request;
+use crate::interface::Response;
+use crate::power::Allocation;
+use crate::power::Direction;
+use crate::power::McNemarMethod;
+use crate::power::ProportionsMethod;
+use crate::power::Tail;
+use crate::power::TestKind;
+use crate::string::dealloc;
+use crate::string::read_from_ptr;
+use crate::string::LENGTH_PREFIX;
 use serde_json::json;
 #[cfg(test)]
 use serde_json::Value;
@@ -38,6 +52,25 @@ fn test_interface(input: &Value, output: f64) {
     assert_eq!(result, output);
 }
 
+fn test_error(input: &Value, code: &str, field: Option<&str>) {
+    let text = input.to_owned().to_string();
+    let returned = handle_received(&text);
+    assert_eq!(returned["error"]["code"], json!(code));
+    assert_eq!(returned["error"]["field"], json!(field));
+}
+
+/// Returns the smallest multiple of `step` at which the power reaches `power`, found by
+/// computing the power at every multiple; sizes the test rejects are skipped.
+fn brute_force_n(test: &TestKind, tail: Tail, power: f64, es: f64, step: f64) -> i64 {
+    (1..)
+        .map(|k| k as f64 * step)
+        .find(|&n| {
+            test.power(tail.clone(), n, ALPHA, es)
+                .is_ok_and(|p| p >= power)
+        })
+        .unwrap() as i64
+}
+
 fn default_input() -> Value {
     json!({
         "n": N,
@@ -73,15 +106,549 @@ fn one_sample_t_test() {
     test_interface(&join(&extra), 54.0);
 }
 
+#[test]
+fn paired_samples_t_test() {
+    // G*Power: t tests - Means: Difference between two dependent means (matched pairs).
+    let join = with_rest("pairedSamplesTTest");
+    let extra = json!({"tail": "1", "analysis": "power"});
+    test_interface(&join(&extra), 0.967);
+    let extra = json!({"tail": "2", "analysis": "power"});
+    test_interface(&join(&extra), 0.934);
+    let extra = json!({"tail": "2", "analysis": "alpha"});
+    test_interface(&join(&extra), 0.067);
+    let extra = json!({"tail": "2", "analysis": "es"});
+    test_interface(&join(&extra), 0.520);
+    let extra = json!({"tail": "1", "analysis": "n"});
+    test_interface(&join(&extra), 45.0);
+    let extra = json!({"tail": "2", "analysis": "n"});
+    test_interface(&join(&extra), 54.0);
+
+    // d_z = |10 - 12| / sqrt(4^2 + 4^2 - 2 * 0.5 * 4 * 4) = 0.5.
+    let groups = json!({"mean1": "10", "mean2": "12", "sd1": "4", "sd2": "4", "rho": "0.5"});
+    let extra = join_json(&groups, &json!({"tail": "2", "analysis": "n", "es": 0.1}));
+    test_interface(&join(&extra), 54.0);
+    let groups = json!({"mean1": 10, "mean2": 12, "sd1": 4, "sd2": 4, "rho": 0.8});
+    let extra = join_json(&groups, &json!({"tail": "2", "analysis": "power", "n": 15}));
+    test_interface(&join(&extra), 0.812);
+    let extra = json!({"mean1": 10, "sd1": 4, "sd2": 4, "rho": 0.5, "analysis": "n"});
+    test_error(&join(&extra), "missing_field", Some("mean2"));
+    let extra = join_json(&groups, &json!({"rho": 1.0, "analysis": "n"}));
+    test_error(&join(&extra), "out_of_bounds", Some("rho"));
+}
+
 #[test]
 fn independent_samples_t_test() {
-    // G*Power only gives 0.392 if you put sample size group 1 and 2 both on n=50.
-    // pwr.t.test(n=50, d=0.5, sig.level=NULL, power=0.95, type="two.sample", alternative="two.sided")
+    // n is the total sample size, so this is G*Power with n1 = n2 = 50, which gives 0.393;
+    // the alpha analysis ignores the lower rejection region.
     let join = with_rest("independentSamplesTTest");
-    let extra = json!({"tail": "2", "analysis": "alpha"});
-    test_interface(&join(&extra), 0.398);
+    let extra = json!({"tail": "2", "n": 100, "analysis": "alpha"});
+    test_interface(&join(&extra), 0.395);
+    let extra = json!({"tail": "2", "n1": "50", "n2": "50", "analysis": "alpha"});
+    test_interface(&join(&extra), 0.395);
     let extra = json!({"tail": "1", "analysis": "n"});
-    test_interface(&join(&extra), 88.0);
+    test_interface(&join(&extra), 176.0);
+}
+
+#[test]
+fn independent_samples_t_test_allocation_ratio() {
+    // G*Power: d = 0.5, α = 0.05, power = 0.95, two tails and N2/N1 = 2 gives 79 and 158.
+    let join = with_rest("independentSamplesTTest");
+    let extra = json!({"tail": "2", "allocRatio": "2", "analysis": "n"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(returned["n"], json!(237));
+    assert_eq!(returned["n1"], json!(79));
+    assert_eq!(returned["n2"], json!(158));
+    assert_eq!(returned["actualPower"], json!(0.951));
+    let extra = json!({"tail": "2", "n1": "30", "n2": "60", "analysis": "power"});
+    test_interface(&join(&extra), 0.599);
+    // As in G*Power, n2 is N2/N1 times the rounded up n1, rounded up.
+    let extra = json!({"tail": "2", "allocRatio": "1.5", "analysis": "n"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(returned["n"], json!(220));
+    assert_eq!(returned["n1"], json!(88));
+    assert_eq!(returned["n2"], json!(132));
+    assert_eq!(returned["actualPower"], json!(0.951));
+    let extra = json!({"allocRatio": "0", "analysis": "n"});
+    test_error(&join(&extra), "out_of_bounds", Some("allocRatio"));
+    let extra = json!({"n1": "30", "n2": "60", "analysis": "n"});
+    test_error(&join(&extra), "invalid_value", Some("n1"));
+    let extra = json!({"n2": "60", "analysis": "n"});
+    test_error(&join(&extra), "invalid_value", Some("n2"));
+    // Each group is large enough on its own, but the two leave no degrees of freedom.
+    let extra = json!({"n1": "1", "n2": "1", "analysis": "power"});
+    test_error(&join(&extra), "out_of_bounds", Some("n"));
+}
+
+#[test]
+fn independent_samples_welch_t_test() {
+    let join = with_rest("independentSamplesWelchTTest");
+    // With equal variances and group sizes, this is the pooled-variance t-test.
+    let extra = json!({"tail": "1", "varRatio": "1", "analysis": "n"});
+    test_interface(&join(&extra), 176.0);
+    // Power drops when the larger variance is in the smaller group.
+    let extra = json!({"tail": "2", "n1": "30", "n2": "60", "varRatio": "4", "analysis": "power"});
+    test_interface(&join(&extra), 0.696);
+    let extra =
+        json!({"tail": "2", "n1": "30", "n2": "60", "sd1": "2", "sd2": "1", "analysis": "power"});
+    test_interface(&join(&extra), 0.511);
+    let extra = json!({"tail": "2", "allocRatio": "2", "varRatio": "4", "es": 0.5, "power": 0.8, "analysis": "n"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(returned["n1"], json!(39));
+    assert_eq!(returned["n2"], json!(78));
+    assert_eq!(returned["df"], json!([114.996]));
+}
+
+#[test]
+fn independent_samples_welch_t_test_errors() {
+    let join = with_rest("independentSamplesWelchTTest");
+    let extra = json!({"analysis": "n"});
+    test_error(&join(&extra), "missing_field", Some("varRatio"));
+    let extra = json!({"sd1": "1", "analysis": "n"});
+    test_error(&join(&extra), "missing_field", Some("sd2"));
+    let extra = json!({"varRatio": "-1", "analysis": "n"});
+    test_error(&join(&extra), "out_of_bounds", Some("varRatio"));
+}
+
+#[test]
+fn equivalence_test() {
+    let join = with_rest("independentSamplesEquivalenceTest");
+    // Margins of half a standard deviation need about 2 (1.645 + 0.842)² / 0.5² = 69 per
+    // group under the normal approximation.
+    let margins = json!({"lower": "-0.5", "upper": "0.5", "es": 0, "power": 0.8});
+    let extra = join_json(&margins, &json!({"analysis": "n"}));
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!((&returned["n1"], &returned["n2"]), (&json!(70), &json!(70)));
+    assert_eq!(returned["ncp"], json!(null));
+    assert_eq!(returned["criticalValue"], json!(1.656));
+    // Integrals of the joint distribution of both t statistics, computed with mpmath.
+    let extra = join_json(&margins, &json!({"n": 140, "analysis": "power"}));
+    test_interface(&join(&extra), 0.806);
+    let extra = json!({"lower": "-0.3", "upper": "0.6", "allocRatio": "2", "es": 0.1, "n": 150, "analysis": "power"});
+    test_interface(&join(&extra), 0.634);
+    let extra = join_json(&margins, &json!({"n": 140, "analysis": "alpha"}));
+    test_interface(&join(&extra), 0.048);
+    // The sensitivity analysis reports the largest effect that still shows equivalence.
+    let extra = join_json(&margins, &json!({"n": 200, "analysis": "es"}));
+    let es = handle_received(&join(&extra).to_string())["es"]
+        .as_f64()
+        .unwrap();
+    let extra = join_json(&margins, &json!({"n": 200, "es": es, "analysis": "power"}));
+    test_interface(&join(&extra), 0.8);
+    let extra = join_json(&margins, &json!({"n": 200, "es": -es, "analysis": "power"}));
+    test_interface(&join(&extra), 0.8);
+    // The paired test works on the differences.
+    let extra = join_json(&margins, &json!({"n": 70, "analysis": "power"}));
+    let power = handle_received(&with_rest("oneSampleEquivalenceTest")(&extra).to_string());
+    let paired = handle_received(&with_rest("pairedSamplesEquivalenceTest")(&extra).to_string());
+    assert_eq!(power["power"], paired["power"]);
+}
+
+#[test]
+fn non_inferiority_test() {
+    // Shifting the null hypothesis by the margin shifts the effect size, and the test is
+    // one-sided whatever the tail.
+    let extra = json!({"tail": "1", "analysis": "power"});
+    let returned = handle_received(&with_rest("oneSampleTTest")(&extra).to_string());
+    let power = returned["power"].as_f64().unwrap();
+    let join = with_rest("oneSampleEquivalenceTest");
+    let extra = json!({"lower": "-0.2", "mode": "nonInferiority", "es": 0.3, "tail": "2", "analysis": "power"});
+    test_interface(&join(&extra), power);
+    // (1.96 + 1.282)² / 0.5² = 42 under the normal approximation.
+    let extra = json!({"lower": "-0.5", "mode": "nonInferiority", "es": 0, "alpha": 0.025, "power": 0.9, "analysis": "n"});
+    test_interface(&join(&extra), 44.0);
+    // Roughly (1.984 + 1.290) / sqrt(100) - 0.5.
+    let extra = json!({"lower": "-0.5", "mode": "nonInferiority", "n": 100, "alpha": 0.025, "power": 0.9, "analysis": "es"});
+    test_interface(&join(&extra), -0.173);
+}
+
+#[test]
+fn equivalence_test_errors() {
+    let join = with_rest("oneSampleEquivalenceTest");
+    let extra = json!({"lower": "-0.5", "analysis": "n"});
+    test_error(&join(&extra), "missing_field", Some("upper"));
+    let extra = json!({"lower": "0.5", "upper": "-0.5", "analysis": "n"});
+    test_error(&join(&extra), "out_of_bounds", Some("upper"));
+    test_error(
+        &join(&json!({"analysis": "n"})),
+        "missing_field",
+        Some("lower"),
+    );
+}
+
+#[test]
+fn rank_tests() {
+    // The t-test needs 105 per group, which grows by the ARE 3/π for normal data.
+    let join = with_rest("mannWhitneyTest");
+    let extra = json!({"tail": "2", "analysis": "n"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(
+        (&returned["n1"], &returned["n2"]),
+        (&json!(110), &json!(110))
+    );
+    let extra = json!({"tail": "2", "parent": "minARE", "analysis": "n"});
+    test_interface(&join(&extra), 244.0);
+    // Laplace data give the rank test the power of a t-test with 1.5 times the sample size.
+    let extra = json!({"tail": "2", "n": 75, "analysis": "power"});
+    let returned = handle_received(&with_rest("oneSampleTTest")(&extra).to_string());
+    let power = returned["power"].as_f64().unwrap();
+    let extra = json!({"tail": "2", "parent": "laplace", "analysis": "power"});
+    test_interface(&with_rest("wilcoxonSignedRankTest")(&extra), power);
+    test_interface(&with_rest("wilcoxonSignedRankPairedTest")(&extra), power);
+    let extra = json!({"tail": "2", "parent": "logistic", "analysis": "es"});
+    test_interface(&with_rest("wilcoxonSignedRankTest")(&extra), 0.496);
+    let extra = json!({"parent": "cauchy", "analysis": "n"});
+    test_error(&join(&extra), "invalid_value", Some("parent"));
+}
+
+#[test]
+fn variance_test() {
+    // Tail probabilities of the scaled chi-squared distribution, computed with mpmath.
+    let join = with_rest("varianceTest");
+    let extra = json!({"tail": "1", "es": 1.5, "power": 0.8, "analysis": "n"});
+    test_interface(&join(&extra), 74.0);
+    let extra = json!({"tail": "2", "es": 1.5, "power": 0.8, "analysis": "n"});
+    test_interface(&join(&extra), 93.0);
+    // A ratio below 1 rejects in the lower tail.
+    let extra = json!({"tail": "1", "es": 0.6, "power": 0.8, "analysis": "n"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(returned["n"], json!(52));
+    assert_eq!(returned["criticalValue"], json!(35.6));
+    let extra = json!({"tail": "2", "es": 0.6, "n": 52, "analysis": "power"});
+    test_interface(&join(&extra), 0.683);
+    let extra = json!({"tail": "1", "es": 0.6, "n": 52, "power": 0.8, "analysis": "alpha"});
+    test_interface(&join(&extra), 0.049);
+    let extra = json!({"tail": "1", "n": 40, "power": 0.8, "analysis": "es"});
+    test_interface(&join(&extra), 1.736);
+    // The sensitivity analysis finds the ratio below 1 that the power analysis accepts.
+    let extra = json!({"tail": "1", "es": 0.5, "n": 50, "analysis": "power"});
+    test_interface(&join(&extra), 0.962);
+    let extra =
+        json!({"tail": "1", "direction": "less", "n": 50, "power": 0.962, "analysis": "es"});
+    test_interface(&join(&extra), 0.5);
+    // Only the size of a standardized mean difference matters.
+    let extra = json!({"direction": "less", "analysis": "es"});
+    test_error(
+        &with_rest("oneSampleTTest")(&extra),
+        "invalid_value",
+        Some("direction"),
+    );
+}
+
+#[test]
+fn two_variances_test() {
+    let join = with_rest("twoVariancesTest");
+    let extra = json!({"tail": "1", "es": 1.5, "power": 0.8, "analysis": "n"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(
+        (&returned["n1"], &returned["n2"]),
+        (&json!(153), &json!(153))
+    );
+    assert_eq!(returned["df"], json!([152.0, 152.0]));
+    // F(59, 29) for 30 and 60 subjects, scaled by the variance ratio.
+    let extra = json!({"tail": "2", "allocRatio": "2", "es": 0.5, "n": 90, "analysis": "power"});
+    test_interface(&join(&extra), 0.595);
+    let extra = json!({"tail": "2", "es": 1.5, "n": 40, "analysis": "power"});
+    test_interface(&join(&extra), 0.135);
+    let extra = json!({"allocRatio": "0", "analysis": "n"});
+    test_error(&join(&extra), "out_of_bounds", Some("allocRatio"));
+}
+
+#[test]
+fn exact_binomial_test() {
+    let join = with_rest("exactBinomialTest");
+    let extra = json!({"p0": "0.5", "tail": "1", "es": 0.15, "power": 0.8, "analysis": "n"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(returned["n"], json!(69));
+    assert_eq!(returned["actualPower"], json!(0.802));
+    assert_eq!(returned["criticalValue"], json!(42.0));
+    assert_eq!(returned["actualAlpha"], json!(0.046));
+    assert_eq!(returned.get("ncp"), None);
+    let extra = json!({"p0": "0.5", "tail": "2", "n": 100, "es": 0.1, "analysis": "power"});
+    test_interface(&join(&extra), 0.462);
+    let extra = json!({"p0": "0.5", "tail": "1", "n": 100, "es": -0.1, "analysis": "power"});
+    test_interface(&join(&extra), 0.623);
+    let extra = json!({"p0": "1", "analysis": "n"});
+    test_error(&join(&extra), "out_of_bounds", Some("p0"));
+    for es in [0.5, 0.6, -0.5] {
+        let extra = json!({"p0": "0.5", "es": es, "analysis": "power"});
+        test_error(&join(&extra), "out_of_bounds", Some("es"));
+    }
+}
+
+#[test]
+fn exact_binomial_test_saw_tooth() {
+    // Power reaches 0.8 at n = 69 but drops below it again at n = 70.
+    let test = TestKind::ExactBinomialTest { p0: 0.5 };
+    assert!(test.power(Tail::OneSided, 69.0, ALPHA, 0.15).unwrap() >= 0.8);
+    assert!(test.power(Tail::OneSided, 70.0, ALPHA, 0.15).unwrap() < 0.8);
+    assert_eq!(test.n(Tail::OneSided, ALPHA, 0.8, 0.15).unwrap().1, 69);
+    // Power reaches 0.75 at n = 60, below the drop to 0.712 at n = 64.
+    assert!(test.power(Tail::OneSided, 64.0, ALPHA, 0.15).unwrap() < 0.75);
+    assert_eq!(test.n(Tail::OneSided, ALPHA, 0.75, 0.15).unwrap().1, 60);
+    for power in [0.7, 0.75, 0.8, 0.9] {
+        for tail in [Tail::OneSided, Tail::TwoSided] {
+            let n = test.n(tail.clone(), ALPHA, power, 0.15).unwrap().1;
+            assert_eq!(n, brute_force_n(&test, tail, power, 0.15, 1.0));
+        }
+    }
+    // The actual α stays below the nominal α.
+    for n in 10..100 {
+        let details = test.details(Tail::TwoSided, n as f64, ALPHA, 0.15).unwrap();
+        assert!(details.actual_alpha.unwrap() <= ALPHA);
+    }
+}
+
+#[test]
+fn two_proportions_test() {
+    let join = with_rest("twoProportionsTest");
+    let design = json!({"p1": "0.3", "p2": "0.5", "tail": "2", "power": 0.8, "analysis": "n"});
+    // G*Power and Fleiss (1981) give 93 and, with continuity correction, 103 per group.
+    let expected = [
+        ("zTest", 93),
+        ("zTestCorrected", 103),
+        ("fisher", 102),
+        ("arcsine", 93),
+    ];
+    for (method, n1) in expected {
+        let extra = join_json(&design, &json!({"method": method}));
+        let returned = handle_received(&join(&extra).to_string());
+        assert_eq!(returned["n1"], json!(n1));
+        assert_eq!(returned["n"], json!(2 * n1));
+    }
+    // pwr.2p2n.test(h = ES.h(0.5, 0.3), n1 = 30, n2 = 60) gives 0.452.
+    let design = json!({"p1": "0.3", "p2": "0.5", "n1": "30", "n2": "60", "analysis": "power"});
+    let expected = [
+        ("zTest", "1", 0.567),
+        ("zTestCorrected", "2", 0.345),
+        ("fisher", "1", 0.477),
+        ("fisher", "2", 0.412),
+        ("arcsine", "2", 0.452),
+    ];
+    for (method, tail, power) in expected {
+        let extra = join_json(&design, &json!({"method": method, "tail": tail}));
+        test_interface(&join(&extra), power);
+    }
+    // Only h is needed with the arcsine method.
+    let extra = json!({"method": "arcsine", "tail": "2", "es": 0.2, "power": 0.8, "analysis": "n"});
+    test_interface(&join(&extra), 786.0);
+    let extra = json!({"p1": "0.3", "tail": "2", "power": 0.8, "analysis": "es"});
+    test_interface(&join(&extra), 0.795);
+}
+
+#[test]
+fn two_proportions_test_fisher_saw_tooth() {
+    // The power of Fisher's exact test is not monotone in n either: for h = 0.6, it is
+    // 0.905 with 63 per group but 0.896 with 64.
+    let test = TestKind::TwoProportionsTest {
+        allocation: Allocation::default(),
+        p1: Some(0.3),
+        p2: None,
+        method: ProportionsMethod::Fisher,
+    };
+    assert_eq!(test.n(Tail::TwoSided, ALPHA, 0.9, 0.6).unwrap().1, 126);
+    for power in [0.7, 0.8, 0.9] {
+        for tail in [Tail::OneSided, Tail::TwoSided] {
+            let n = test.n(tail.clone(), ALPHA, power, 0.6).unwrap().1;
+            assert_eq!(n, brute_force_n(&test, tail, power, 0.6, 2.0));
+        }
+    }
+}
+
+#[test]
+fn two_proportions_test_errors() {
+    let join = with_rest("twoProportionsTest");
+    let extra = json!({"analysis": "n"});
+    test_error(&join(&extra), "missing_field", Some("p1"));
+    let extra = json!({"p1": "0.3", "p2": "1.5", "analysis": "n"});
+    test_error(&join(&extra), "out_of_bounds", Some("p2"));
+    let extra = json!({"p1": "0.3", "method": "wald", "analysis": "n"});
+    test_error(&join(&extra), "invalid_value", Some("method"));
+}
+
+#[test]
+fn mcnemar_test() {
+    let join = with_rest("mcNemarTest");
+    // Connor (1987): n = (z_α/2 √pD + z_β √(pD - δ²))² / δ² = 233.1 for δ = p12 - p21 = 0.1.
+    let extra = json!({"propDiscordant": "0.3", "method": "asymptotic", "tail": "2", "es": 2, "power": 0.8, "analysis": "n"});
+    test_interface(&join(&extra), 234.0);
+    let extra =
+        json!({"propDiscordant": "0.3", "tail": "1", "es": 2, "power": 0.8, "analysis": "n"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(returned["n"], json!(199));
+    assert_eq!(returned["actualPower"], json!(0.801));
+    assert_eq!(returned["discordantPairs"], json!(59.7));
+    // The discordant proportions give an odds ratio of 2 and a proportion of 0.3.
+    let extra = json!({"p12": "0.2", "p21": "0.1", "tail": "2", "n": 100, "analysis": "power"});
+    test_interface(&join(&extra), 0.373);
+    let extra =
+        json!({"propDiscordant": "0.3", "tail": "2", "n": 100, "power": 0.373, "analysis": "es"});
+    let es = handle_received(&join(&extra).to_string())["es"]
+        .as_f64()
+        .unwrap();
+    assert!(1.0 < es && es <= 2.0);
+}
+
+#[test]
+fn mcnemar_test_saw_tooth() {
+    // If all pairs are discordant, the power is that of a binomial test, which reaches 0.75
+    // one-sided at 30 pairs for an odds ratio of 2.5 but not at 32.
+    let test = |discordant| TestKind::McNemarTest {
+        prop_discordant: Some(discordant),
+        p12: None,
+        p21: None,
+        method: McNemarMethod::Exact,
+    };
+    assert_eq!(test(1.0).n(Tail::OneSided, ALPHA, 0.75, 2.5).unwrap().1, 30);
+    for (discordant, es) in [(1.0, 2.5), (0.3, 2.0)] {
+        let test = test(discordant);
+        for power in [0.6, 0.65, 0.75, 0.8] {
+            for tail in [Tail::OneSided, Tail::TwoSided] {
+                let n = test.n(tail.clone(), ALPHA, power, es).unwrap().1;
+                assert_eq!(n, brute_force_n(&test, tail, power, es, 1.0));
+            }
+        }
+    }
+}
+
+#[test]
+fn mcnemar_test_errors() {
+    let join = with_rest("mcNemarTest");
+    let extra = json!({"analysis": "n"});
+    test_error(&join(&extra), "missing_field", Some("propDiscordant"));
+    let extra = json!({"p12": "0.2", "analysis": "n"});
+    test_error(&join(&extra), "missing_field", Some("p21"));
+    let extra = json!({"propDiscordant": "1.2", "analysis": "n"});
+    test_error(&join(&extra), "out_of_bounds", Some("propDiscordant"));
+}
+
+#[test]
+fn correlation_test() {
+    let join = with_rest("correlationTest");
+    // G*Power: Exact - Correlation: Bivariate normal model gives 84 and a critical r of 0.2146.
+    let extra = json!({"tail": "2", "es": 0.3, "power": 0.8, "analysis": "n"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(returned["n"], json!(84));
+    assert_eq!(returned["df"], json!([82.0]));
+    assert_eq!(returned["criticalValue"], json!(0.215));
+    let extra = json!({"method": "fisherZ", "tail": "2", "es": 0.3, "power": 0.8, "analysis": "n"});
+    test_interface(&join(&extra), 85.0);
+    // Both tails of the skewed null distribution of r for ρ0 = 0.3; integrating the density
+    // of r gives a power of 0.6599.
+    let extra = json!({"rho0": "0.3", "tail": "2", "es": 0.5, "n": 100, "analysis": "power"});
+    test_interface(&join(&extra), 0.66);
+    // A correlation below ρ0 is tested in the lower tail, with power 0.9509 at n = 76.
+    let extra = json!({"rho0": "0.6", "es": 0.3, "power": 0.95, "analysis": "n"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(returned["n"], json!(76));
+    assert_eq!(returned["criticalValue"], json!(0.466));
+    // G*Power manual, ρ0 = 0.6 against ρ = 0.65: integrating the density of r gives a power of
+    // 0.95003 at n = 1928 and 0.94993 at n = 1927 two-sided, and 0.95003 at n = 1606 one-sided.
+    let extra = json!({"rho0": "0.6", "tail": "2", "es": 0.65, "power": 0.95, "analysis": "n"});
+    test_interface(&join(&extra), 1928.0);
+    let extra = json!({"rho0": "0.6", "es": 0.65, "power": 0.95, "analysis": "n"});
+    test_interface(&join(&extra), 1606.0);
+    let extra = json!({"rho0": "0.3", "tail": "2", "n": 100, "power": 0.66, "analysis": "es"});
+    test_interface(&join(&extra), 0.5);
+    let extra =
+        json!({"rho0": "0.6", "direction": "less", "n": 76, "power": 0.9509, "analysis": "es"});
+    test_interface(&join(&extra), 0.3);
+    let extra = json!({"rho0": "1", "analysis": "n"});
+    test_error(&join(&extra), "out_of_bounds", Some("rho0"));
+}
+
+#[test]
+fn two_correlations_test() {
+    let join = with_rest("twoCorrelationsTest");
+    // q = atanh 0.5 - atanh 0.3 = 0.2398 needs 1 / (1 / (n1 - 3) + 1 / (n2 - 3)) = 136.5.
+    let extra = json!({"rho1": "0.3", "rho2": "0.5", "tail": "2", "power": 0.8, "analysis": "n"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(returned["n"], json!(554));
+    assert_eq!(returned["n1"], json!(277));
+    let extra = json!({"allocRatio": "2", "es": 0.3, "power": 0.95, "analysis": "n"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(
+        (&returned["n1"], &returned["n2"]),
+        (&json!(183), &json!(366))
+    );
+    let extra = json!({"n1": "50", "n2": "80", "tail": "2", "es": 0.5, "analysis": "power"});
+    test_interface(&join(&extra), 0.771);
+    let extra = json!({"rho1": "0.3", "analysis": "n"});
+    test_error(&join(&extra), "missing_field", Some("rho2"));
+    let extra = json!({"rho1": "0.3", "rho2": "-1", "analysis": "n"});
+    test_error(&join(&extra), "out_of_bounds", Some("rho2"));
+}
+
+#[test]
+fn dependent_correlations_test() {
+    // A simulation of Steiger's test with 4000 samples rejects in 68.9% (SE 0.7%).
+    let join = with_rest("dependentCorrelationsCommonIndexTest");
+    let correlations = json!({"rho12": "0.4", "rho13": "0.6", "rho23": "0.5", "tail": "2"});
+    let extra = join_json(&correlations, &json!({"analysis": "power", "n": 100}));
+    test_interface(&join(&extra), 0.697);
+    let extra = join_json(&correlations, &json!({"analysis": "n", "power": 0.8}));
+    test_interface(&join(&extra), 128.0);
+    // q = atanh 0.6 - atanh 0.4 = 0.2695.
+    let extra = json!({"rho12": "0.4", "rho23": "0.5", "tail": "2", "n": 100, "power": 0.697, "analysis": "es"});
+    test_interface(&join(&extra), 0.269);
+
+    // A simulation with 3000 samples rejects in 65.6% (SE 0.9%).
+    let join = with_rest("dependentCorrelationsNoCommonIndexTest");
+    let correlations = json!({"rho12": "0.4", "rho13": "0.3", "rho14": "0.2", "rho23": "0.3", "rho24": "0.1", "tail": "2"});
+    let extra = join_json(
+        &correlations,
+        &json!({"rho34": "0.6", "analysis": "power", "n": 150}),
+    );
+    test_interface(&join(&extra), 0.654);
+}
+
+#[test]
+fn dependent_correlations_test_errors() {
+    let join = with_rest("dependentCorrelationsCommonIndexTest");
+    let extra = json!({"rho12": "0.4", "analysis": "n"});
+    test_error(&join(&extra), "missing_field", Some("rho23"));
+    let extra = json!({"rho12": "0.4", "rho13": "1.5", "rho23": "0.5", "analysis": "n"});
+    test_error(&join(&extra), "out_of_bounds", Some("rho13"));
+    // Variable 1 cannot be close to both 2 and 3 if those are far apart.
+    let extra = json!({"rho12": "0.9", "rho13": "0.9", "rho23": "-0.5", "analysis": "n"});
+    test_error(&join(&extra), "out_of_bounds", None);
+}
+
+#[test]
+fn survival_test() {
+    let join = with_rest("survivalTest");
+    // Schoenfeld (1981): 4 (1.96 + 0.842)² / ln(0.5)² = 65.4 events.
+    let everyone = json!({"eventProb1": "1", "eventProb2": "1", "tail": "2", "es": 0.5, "power": 0.8, "analysis": "n"});
+    let returned = handle_received(&join(&everyone).to_string());
+    assert_eq!(returned["n"], json!(66));
+    assert_eq!(returned["events"], json!(66.0));
+    // Freedman (1982): (1.96 + 0.842)² (1 + 0.5)² / (1 - 0.5)² = 70.6 events, in equal groups.
+    let extra = join_json(&everyone, &json!({"method": "freedman"}));
+    test_interface(&join(&extra), 72.0);
+    // With a median survival of 12 months, 24 months of accrual, and 12 months of follow-up,
+    // 73.0% of group 1 and 60.6% of group 2 have an event.
+    let extra = json!({"median1": "12", "accrual": "24", "followUp": "12", "allocRatio": "2", "tail": "2", "es": 0.7, "power": 0.8, "analysis": "n"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(
+        (&returned["n1"], &returned["n2"]),
+        (&json!(143), &json!(286))
+    );
+    assert_eq!(returned["events"], json!(277.641));
+    let extra = json!({"median1": "12", "followUp": "12", "tail": "2", "es": 0.7, "n": 300, "analysis": "power"});
+    test_interface(&join(&extra), 0.538);
+}
+
+#[test]
+fn survival_test_errors() {
+    let join = with_rest("survivalTest");
+    test_error(
+        &join(&json!({"analysis": "n"})),
+        "missing_field",
+        Some("median1"),
+    );
+    let extra = json!({"median1": "12", "analysis": "n"});
+    test_error(&join(&extra), "missing_field", Some("followUp"));
+    let extra = json!({"eventProb1": "0.5", "eventProb2": "1.5", "analysis": "n"});
+    test_error(&join(&extra), "out_of_bounds", Some("eventProb2"));
 }
 
 #[test]
@@ -130,6 +697,69 @@ fn increase_multiple_regression() {
     test_interface(&join(&extra), 35.0);
 }
 
+#[test]
+fn logistic_regression() {
+    let join = with_rest("logisticRegression");
+    // G*Power manual, logistic regression example, which replicates Hsieh et al. (1998),
+    // Table II: a standard normal predictor, p0 = 0.5, and an odds ratio of 1.5 need 317
+    // subjects for a power of 0.95 in a two-sided test.
+    let manual = json!({"p0": "0.5", "tail": "2", "es": 1.5, "analysis": "n"});
+    let extra = join_json(&manual, &json!({"method": "hsieh"}));
+    test_interface(&join(&extra), 317.0);
+    // Demidenko (2007) uses the variances of the slope under both hypotheses, 4 and 4.488
+    // times 1 / N, which gives (1.96 sqrt(4) + 1.645 sqrt(4.488))² / ln(1.5)² = 333.5.
+    test_interface(&join(&manual), 334.0);
+    // Hsieh et al. (1998), equation 1: (1.645 + 1.645)² / (0.25 ln(1.5)²) = 263.4.
+    let extra = json!({"p0": "0.5", "method": "hsieh", "es": 1.5, "analysis": "n"});
+    test_interface(&join(&extra), 264.0);
+    // A binary predictor with 30% ones, where the other predictors explain 20% of it.
+    let predictor = json!({"p0": "0.2", "xDistribution": "binomial", "xProp": "0.3", "r2Other": "0.2", "tail": "2"});
+    let extra = join_json(&predictor, &json!({"es": 2, "n": 200, "analysis": "power"}));
+    test_interface(&join(&extra), 0.399);
+    let extra = join_json(&extra, &json!({"method": "hsieh"}));
+    test_interface(&join(&extra), 0.444);
+    // The odds ratio of p0 = 0.1 and p1 = 0.2 is 2.25.
+    let extra =
+        json!({"p0": "0.1", "p1": "0.2", "xSd": "0.5", "tail": "2", "n": 150, "analysis": "power"});
+    let power = handle_received(&join(&extra).to_string())["power"].clone();
+    let extra =
+        json!({"p0": "0.1", "xSd": "0.5", "tail": "2", "es": 2.25, "n": 150, "analysis": "power"});
+    test_interface(&join(&extra), power.as_f64().unwrap());
+}
+
+#[test]
+fn logistic_regression_errors() {
+    let join = with_rest("logisticRegression");
+    test_error(
+        &join(&json!({"analysis": "n"})),
+        "missing_field",
+        Some("p0"),
+    );
+    let extra = json!({"p0": "0.5", "r2Other": "1", "analysis": "n"});
+    test_error(&join(&extra), "out_of_bounds", Some("r2Other"));
+    let extra = json!({"p0": "0.5", "xDistribution": "uniform", "analysis": "n"});
+    test_error(&join(&extra), "invalid_value", Some("xDistribution"));
+}
+
+#[test]
+fn poisson_regression() {
+    let join = with_rest("poissonRegression");
+    // Signorini (1991): (1.645 + 1.645 exp(-ln(1.3)² / 4))² / (0.85 ln(1.3)²) = 181.8.
+    let extra = json!({"baseRate": "0.85", "es": 1.3, "analysis": "n"});
+    test_interface(&join(&extra), 182.0);
+    // G*Power manual, binary X with π = 0.5: the slope variances are 4 under the null
+    // hypothesis and 2 + 2 / 1.3 under the alternative, so
+    // (1.645 · 2 + 1.645 · 1.881)² / (0.85 ln(1.3)²) = 696.6.
+    let extra = json!({"baseRate": "0.85", "xDistribution": "binomial", "xProp": "0.5", "es": 1.3, "analysis": "n"});
+    test_interface(&join(&extra), 697.0);
+    // The variances of the slope are 1 / (0.4 · 0.6) under the null hypothesis and
+    // 0.92 / (0.4 · 0.8 · 0.6) under the alternative, with 270 expected events at x = 0.
+    let extra = json!({"baseRate": "0.5", "exposure": "2", "xDistribution": "binomial", "xProp": "0.4", "r2Other": "0.1", "tail": "2", "es": 0.8, "n": 300, "analysis": "power"});
+    test_interface(&join(&extra), 0.44);
+    let extra = json!({"baseRate": "0", "analysis": "n"});
+    test_error(&join(&extra), "out_of_bounds", Some("baseRate"));
+}
+
 #[test]
 fn one_way_anova_test() {
     let k = "5";
@@ -159,6 +789,48 @@ fn two_way_anova_test() {
     test_interface(&join(&extra), 107.0);
 }
 
+#[test]
+fn hotelling_t2_test() {
+    let join = with_rest("oneSampleHotellingT2Test");
+    // F(3, n - 3) with λ = n Δ².
+    let extra = json!({"nResponses": "3", "analysis": "n"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(returned["n"], json!(73));
+    assert_eq!(returned["df"], json!([3.0, 70.0]));
+    assert_eq!(returned["ncp"], json!(18.25));
+    let join = with_rest("twoSampleHotellingT2Test");
+    let extra = json!({"nResponses": "3", "analysis": "n"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(
+        (&returned["n1"], &returned["n2"]),
+        (&json!(140), &json!(140))
+    );
+    assert_eq!(returned["df"], json!([3.0, 276.0]));
+    // With two groups, Pillai's V gives the same test with f² = Δ²/4.
+    let extra = json!({"nResponses": "3", "analysis": "power"});
+    let power = handle_received(&join(&extra).to_string())["power"].clone();
+    let join = with_rest("globalMANOVA");
+    let extra = json!({"k": "2", "nResponses": "3", "es": ES / 2.0, "analysis": "power"});
+    test_interface(&join(&extra), power.as_f64().unwrap());
+}
+
+#[test]
+fn global_manova() {
+    let join = with_rest("globalMANOVA");
+    let design = json!({"k": "3", "nResponses": "2", "es": 0.25, "analysis": "n"});
+    let returned = handle_received(&join(&design).to_string());
+    assert_eq!(returned["n"], json!(151));
+    assert_eq!(returned["df"], json!([4.0, 296.0]));
+    // Rao's approximation has g = 2 and 2 (n - 3 - 1/2) - 1 error df.
+    let extra = join_json(&design, &json!({"statistic": "wilksLambda"}));
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(returned["df"], json!([4.0, 294.0]));
+    let extra = join_json(&design, &json!({"statistic": "hotellingLawley"}));
+    test_interface(&join(&extra), 152.0);
+    let extra = json!({"k": "3", "nResponses": "0", "analysis": "n"});
+    test_error(&join(&extra), "out_of_bounds", Some("nResponses"));
+}
+
 #[test]
 fn ancova_test() {
     let k = "5";
@@ -201,7 +873,7 @@ fn between_repeated_anova_test() {
     let extra = json!({"k": k, "m": m, "rho": rho, "analysis": "es"});
     test_interface(&join(&extra), 0.411);
     let extra = json!({"k": k, "m": m, "rho": rho, "analysis": "n"});
-    test_interface(&join(&extra), 35.0); // G*Power gives 36
+    test_interface(&join(&extra), 36.0); // Rounded up to equal group sizes, as in G*Power.
 
     let k = "5";
     let m = "10";
@@ -214,7 +886,7 @@ fn between_repeated_anova_test() {
     let extra = json!({"k": k, "m": m, "rho": rho, "analysis": "es"});
     test_interface(&join(&extra), 0.566);
     let extra = json!({"k": k, "m": m, "rho": rho, "analysis": "n"});
-    test_interface(&join(&extra), 63.0); // G*Power gives 65
+    test_interface(&join(&extra), 65.0);
 }
 
 #[test]
@@ -251,11 +923,10 @@ fn within_repeated_anova_test() {
     test_interface(&join_json(&join, &extra), 0.481);
     let join = with_rest("withinRepeatedANOVA");
     let extra = json!({"k": k, "m": m, "rho": rho, "epsilon": epsilon, "analysis": "n"});
-    test_interface(&join(&extra), 10.0);
+    test_interface(&join(&extra), 12.0); // 10 subjects suffice, in unequal groups.
 }
 
 #[test]
-#[should_panic(expected = "lower bound of ε corresponds to 1 / (number of measurements - 1)")]
 fn within_repeated_anova_epsilon_error() {
     let k = "4";
     let m = "2";
@@ -265,7 +936,12 @@ fn within_repeated_anova_epsilon_error() {
     let join =
         json!({"n": n, "alpha": ALPHA, "power": POWER, "es": ES, "test": "withinRepeatedANOVA"});
     let extra = json!({"k": k, "m": m, "rho": rho, "epsilon": epsilon, "analysis": "alpha"});
-    test_interface(&join_json(&join, &extra), 0.123);
+    let returned = handle_received(&join_json(&join, &extra).to_string());
+    assert_eq!(
+        returned["error"]["message"],
+        "lower bound of ε corresponds to 1 / (number of measurements - 1)"
+    );
+    test_error(&join_json(&join, &extra), "out_of_bounds", Some("epsilon"));
 }
 
 #[test]
@@ -284,7 +960,7 @@ fn within_between_repeated_anova_test() {
     test_interface(&join_json(&join, &extra), 0.644);
     let join = with_rest("withinBetweenRepeatedANOVA");
     let extra = json!({"k": k, "m": m, "rho": rho, "epsilon": epsilon, "analysis": "n"});
-    test_interface(&join(&extra), 22.0); // G*Power gives 24
+    test_interface(&join(&extra), 24.0); // Rounded up to equal group sizes, as in G*Power.
 
     let k = "4";
     let m = "3";
@@ -300,11 +976,10 @@ fn within_between_repeated_anova_test() {
     test_interface(&join_json(&join, &extra), 0.539);
     let join = with_rest("withinBetweenRepeatedANOVA");
     let extra = json!({"k": k, "m": m, "rho": rho, "epsilon": epsilon, "analysis": "n"});
-    test_interface(&join(&extra), 14.0); // G*Power gives 16
+    test_interface(&join(&extra), 16.0);
 }
 
 #[test]
-#[should_panic(expected = "lower bound of ε corresponds to 1 / (number of measurements - 1)")]
 fn within_between_repeated_anova_epsilon_error() {
     let k = "4";
     let m = "2";
@@ -313,5 +988,477 @@ fn within_between_repeated_anova_epsilon_error() {
     let n = 12.0;
     let join = json!({"n": n, "alpha": ALPHA, "power": POWER, "es": ES, "test": "withinBetweenRepeatedANOVA"});
     let extra = json!({"k": k, "m": m, "rho": rho, "epsilon": epsilon, "analysis": "alpha"});
-    test_interface(&join_json(&join, &extra), 0.123);
+    let returned = handle_received(&join_json(&join, &extra).to_string());
+    assert_eq!(
+        returned["error"]["message"],
+        "lower bound of ε corresponds to 1 / (number of measurements - 1)"
+    );
+    test_error(&join_json(&join, &extra), "out_of_bounds", Some("epsilon"));
+}
+
+#[test]
+fn repeated_manova_test() {
+    // The between factor is tested on the mean of the measurements either way, so the
+    // univariate effect size f with u = 4 / (1 + 3 · 0.5) gives f(V) = f sqrt(u).
+    let extra = json!({"k": "3", "m": "4", "rho": "0.5", "analysis": "power"});
+    let power =
+        handle_received(&with_rest("betweenRepeatedANOVA")(&extra).to_string())["power"].clone();
+    let join = with_rest("betweenRepeatedMANOVA");
+    let extra = json!({"k": "3", "m": "4", "es": ES * 1.6f64.sqrt(), "analysis": "power"});
+    test_interface(&join(&extra), power.as_f64().unwrap());
+
+    let join = with_rest("withinRepeatedMANOVA");
+    let design = json!({"k": "2", "m": "3", "es": 0.25, "analysis": "n"});
+    let returned = handle_received(&join(&design).to_string());
+    assert_eq!(returned["n"], json!(251));
+    assert_eq!(returned["df"], json!([2.0, 248.0]));
+    assert_eq!(returned["ncp"], json!(15.688));
+    // With two groups, the interaction has a single nonzero eigenvalue too.
+    let join = with_rest("withinBetweenRepeatedMANOVA");
+    test_interface(&join(&design), 251.0);
+    let extra = json!({"k": "3", "m": "3", "es": 0.25, "analysis": "n"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(
+        returned["df"],
+        json!([4.0, 2.0 * (returned["n"].as_f64().unwrap() - 3.0)])
+    );
+    let extra = json!({"k": "3", "m": "1", "analysis": "n"});
+    test_error(&join(&extra), "out_of_bounds", Some("m"));
+}
+
+#[test]
+fn generic_tests() {
+    // Expressions in n reproduce the named tests.
+    let returned =
+        handle_received(&with_rest("oneSampleTTest")(&json!({"analysis": "n"})).to_string());
+    let extra = json!({"df": "n - 1", "ncp": "sqrt(n) * es", "analysis": "n"});
+    test_interface(
+        &with_rest("genericT")(&extra),
+        returned["n"].as_f64().unwrap(),
+    );
+    let extra = json!({"nPredictors": "3", "analysis": "es"});
+    let join = with_rest("deviationFromZeroMultipleRegression");
+    let returned = handle_received(&join(&extra).to_string());
+    let extra = json!({"df1": "3", "df2": "n - 4", "ncp": "es^2 * n", "analysis": "es"});
+    test_interface(
+        &with_rest("genericF")(&extra),
+        returned["es"].as_f64().unwrap(),
+    );
+    let extra = json!({"df": 5, "ncp": "es^2 * n", "es": 0.3, "power": 0.8, "analysis": "n"});
+    test_interface(&with_rest("genericChisq")(&extra), 143.0);
+    // Without an expression, the effect size is the noncentrality parameter.
+    let extra = json!({"df": 20, "es": 2.5, "analysis": "power"});
+    test_interface(&with_rest("genericT")(&extra), 0.779);
+    // 1.960 + 0.842.
+    let extra = json!({"tail": "2", "power": 0.8, "analysis": "es"});
+    test_interface(&with_rest("genericZ")(&extra), 2.802);
+    // The search starts at n = 6, the first sample size with positive degrees of freedom.
+    let extra =
+        json!({"df": "n - 5", "ncp": "sqrt(n) * es", "es": 2, "power": 0.8, "analysis": "n"});
+    test_interface(&with_rest("genericT")(&extra), 7.0);
+    let extra =
+        json!({"df": "n - 500000", "ncp": "sqrt(n) * es", "es": 2, "power": 0.8, "analysis": "n"});
+    test_interface(&with_rest("genericT")(&extra), 500001.0);
+}
+
+#[test]
+fn generic_test_errors() {
+    let join = with_rest("genericT");
+    test_error(
+        &join(&json!({"analysis": "n"})),
+        "missing_field",
+        Some("df"),
+    );
+    let extra = json!({"df": "n - 1", "ncp": "sqrt(n", "analysis": "n"});
+    test_error(&join(&extra), "invalid_value", Some("ncp"));
+    let extra = json!({"df": "n - 1", "ncp": "sqrt(m)", "analysis": "n"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(returned["error"]["message"], json!("unknown name \"m\""));
+    let nested = format!("{}n{}", "(".repeat(1000), ")".repeat(1000));
+    let extra = json!({"df": nested, "analysis": "n"});
+    test_error(&join(&extra), "invalid_value", Some("df"));
+    let extra = json!({"df1": "2", "df2": "-5", "analysis": "n"});
+    test_error(&with_rest("genericF")(&extra), "out_of_bounds", Some("df2"));
+}
+
+#[test]
+fn values_out_of_bounds() {
+    let join = with_rest("oneSampleTTest");
+    for n in [1, -5] {
+        let extra = json!({"n": n, "analysis": "power"});
+        test_error(&join(&extra), "out_of_bounds", Some("n"));
+    }
+    for alpha in [0.0, 1.0, 1.5] {
+        let extra = json!({"alpha": alpha, "analysis": "power"});
+        test_error(&join(&extra), "out_of_bounds", Some("alpha"));
+    }
+    let extra = json!({"power": 1, "analysis": "n"});
+    test_error(&join(&extra), "out_of_bounds", Some("power"));
+    let extra = json!({"power": 0, "analysis": "es"});
+    test_error(&join(&extra), "out_of_bounds", Some("power"));
+    // The sample size is checked against the degrees of freedom of the test.
+    let join = with_rest("independentSamplesTTest");
+    let extra = json!({"n": 2, "analysis": "power"});
+    test_error(&join(&extra), "out_of_bounds", Some("n"));
+    let join = with_rest("independentSamplesWelchTTest");
+    let extra = json!({"n1": "1", "n2": "10", "varRatio": "2", "analysis": "power"});
+    test_error(&join(&extra), "out_of_bounds", Some("n1"));
+    let extra = json!({"n": 3, "varRatio": "2", "analysis": "alpha"});
+    test_error(&join(&extra), "out_of_bounds", Some("n"));
+    // Exact tests need a whole number of subjects.
+    let join = with_rest("exactBinomialTest");
+    let extra = json!({"p0": "0.5", "n": 10.5, "es": 0.2, "analysis": "power"});
+    test_error(&join(&extra), "out_of_bounds", Some("n"));
+    // Ratios are positive.
+    let join = with_rest("survivalTest");
+    for es in [-1, 0] {
+        let extra = json!({"eventProb1": "1", "eventProb2": "1", "es": es, "analysis": "power"});
+        test_error(&join(&extra), "out_of_bounds", Some("es"));
+    }
+    let join = with_rest("logisticRegression");
+    let extra = json!({"p0": "0.5", "es": 0, "analysis": "power"});
+    test_error(&join(&extra), "out_of_bounds", Some("es"));
+    let join = with_rest("varianceTest");
+    let extra = json!({"es": 0, "analysis": "n"});
+    test_error(&join(&extra), "out_of_bounds", Some("es"));
+    // Groups and measurements are counted from 2, except a single group of measurements or
+    // a single measurement of groups.
+    let join = with_rest("oneWayANOVA");
+    for k in ["0", "-3", "1"] {
+        test_error(
+            &join(&json!({"k": k, "analysis": "power"})),
+            "out_of_bounds",
+            Some("k"),
+        );
+    }
+    let join = with_rest("withinRepeatedANOVA");
+    let extra = json!({"k": "1", "m": "1", "rho": "0.5", "epsilon": "1", "analysis": "power"});
+    test_error(&join(&extra), "out_of_bounds", Some("m"));
+    let extra = json!({"k": "1", "m": "3", "rho": "0.5", "epsilon": "1", "analysis": "power"});
+    assert!(handle_received(&join(&extra).to_string())["power"].is_number());
+    let join = with_rest("betweenRepeatedANOVA");
+    let extra = json!({"k": "3", "m": "0", "rho": "0.5", "analysis": "power"});
+    test_error(&join(&extra), "out_of_bounds", Some("m"));
+}
+
+#[test]
+fn malformed_requests() {
+    let returned = handle_received("{\"test\": ");
+    assert_eq!(returned["error"]["code"], "invalid_json");
+    assert_eq!(
+        returned["error"]["message"],
+        "Failed to parse json: EOF while parsing a value at line 1 column 9"
+    );
+    let returned = handle_received("[1, 2]");
+    assert_eq!(returned["error"]["code"], "invalid_json");
+
+    let join = with_rest("oneSampleTTest");
+    let extra = json!({"analysis": "n"});
+    test_error(&with_base(&extra), "missing_field", Some("test"));
+    let extra = json!({"analysis": "n", "test": "nonExistingTest"});
+    test_error(&with_base(&extra), "unknown_test", Some("test"));
+    test_error(
+        &join(&json!({"analysis": "q"})),
+        "unknown_analysis",
+        Some("analysis"),
+    );
+    test_error(
+        &join(&json!({"analysis": 1})),
+        "invalid_type",
+        Some("analysis"),
+    );
+    let extra = json!({"analysis": "power", "alpha": true});
+    test_error(&join(&extra), "invalid_type", Some("alpha"));
+    let extra = json!({"analysis": "power", "alpha": "0.05a"});
+    test_error(&join(&extra), "invalid_value", Some("alpha"));
+    let extra = json!({"analysis": "power", "tail": "3"});
+    test_error(&join(&extra), "invalid_value", Some("tail"));
+
+    let join = with_rest("oneWayANOVA");
+    test_error(
+        &join(&json!({"analysis": "power"})),
+        "missing_field",
+        Some("k"),
+    );
+    let extra = json!({"analysis": "power", "k": "five"});
+    test_error(&join(&extra), "invalid_value", Some("k"));
+    let extra = json!({"analysis": "power", "k": "3", "tail": "3"});
+    test_error(&join(&extra), "invalid_value", Some("tail"));
+    let extra = json!({"analysis": "power", "k": "five", "n": "five"});
+    test_error(&join(&extra), "invalid_value", Some("n"));
+    let join = with_rest("twoProportionsTest");
+    let extra = json!({"analysis": "power", "p1": "0.5", "p2": "0.5", "method": "0.5"});
+    test_error(&join(&extra), "invalid_value", Some("method"));
+    let join = with_rest("independentSamplesTTest");
+    let extra = json!({"analysis": "power", "allocRatio": "1a", "n1": "30"});
+    test_error(&join(&extra), "invalid_value", Some("allocRatio"));
+}
+
+#[test]
+fn parameters_that_do_not_apply() {
+    let extra = json!({"analysis": "power", "k": "foo"});
+    test_error(
+        &with_rest("oneSampleTTest")(&extra),
+        "invalid_value",
+        Some("k"),
+    );
+    let extra = json!({"analysis": "power", "k": "5", "m": "2"});
+    test_error(
+        &with_rest("oneWayANOVA")(&extra),
+        "invalid_value",
+        Some("m"),
+    );
+    let extra = json!({"analysis": "power", "p1": "0.3", "p2": "0.5", "k": "2"});
+    test_error(
+        &with_rest("twoProportionsTest")(&extra),
+        "invalid_value",
+        Some("k"),
+    );
+}
+
+#[test]
+fn effect_size_is_optional_if_derived() {
+    let join = with_rest("twoProportionsTest");
+    let mut input = join(&json!({"analysis": "power", "p1": "0.3", "p2": "0.5"}));
+    input.as_object_mut().unwrap().remove("es");
+    assert_eq!(handle_received(&input.to_string())["power"], json!(0.418));
+    let join = with_rest("pairedSamplesTTest");
+    let mut input = join(&json!({"analysis": "power"}));
+    input.as_object_mut().unwrap().remove("es");
+    test_error(&input, "missing_field", Some("es"));
+    let mut input = join(&json!({"analysis": "es"}));
+    input.as_object_mut().unwrap().remove("es");
+    test_interface(&input, 0.472);
+}
+
+#[test]
+fn numbers_or_numeric_strings() {
+    let join = with_rest("oneWayANOVA");
+    let extra = json!({"k": 5, "analysis": "power", "tail": 1});
+    test_interface(&join(&extra), 0.773);
+    let extra = json!({"k": "5", "analysis": "power", "alpha": "0.05", "n": "50"});
+    test_interface(&join(&extra), 0.773);
+}
+
+#[test]
+fn compromise_analysis() {
+    // The root of β = α at the one-sided t-test, computed with mpmath.
+    let join = with_rest("oneSampleTTest");
+    let extra = json!({"tail": 1, "n": 30, "betaAlphaRatio": 1, "analysis": "compromise"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(returned["alpha"], json!(0.089));
+    assert_eq!(returned["power"], json!(0.911));
+    assert_eq!(returned["criticalValue"], json!(1.381));
+    let join = with_rest("independentSamplesTTest");
+    let extra = json!({"tail": 2, "n": 64, "betaAlphaRatio": "4", "analysis": "compromise"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(returned["alpha"], json!(0.095));
+    assert_eq!(returned["power"], json!(0.621));
+    // Exact tests report the actual α of the smallest region with β at most q·α.
+    let join = with_rest("exactBinomialTest");
+    let extra =
+        json!({"p0": 0.5, "tail": 1, "es": 0.15, "betaAlphaRatio": 2, "analysis": "compromise"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(returned["alpha"], json!(0.101));
+    assert_eq!(returned["power"], json!(0.814));
+    assert_eq!(returned["criticalValue"], json!(30.0));
+    let join = with_rest("oneSampleTTest");
+    let extra = json!({"analysis": "compromise"});
+    test_error(&join(&extra), "missing_field", Some("betaAlphaRatio"));
+    let extra = json!({"betaAlphaRatio": 0, "analysis": "compromise"});
+    test_error(&join(&extra), "out_of_bounds", Some("betaAlphaRatio"));
+    // Callers other than the interface get the same error.
+    for ratio in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        let error = TestKind::OneSampleTTest
+            .compromise(Tail::OneSided, N, ES, ratio)
+            .unwrap_err();
+        assert_eq!(error.field.unwrap(), "betaAlphaRatio");
+    }
+}
+
+#[test]
+fn typed_request() {
+    let request = Request {
+        test: TestKind::OneWayANOVA { k: 5 },
+        analysis: Analysis::Power,
+        n: N,
+        alpha: ALPHA,
+        power: POWER,
+        es: Some(ES),
+        tail: Tail::OneSided,
+        direction: Direction::Greater,
+        beta_alpha_ratio: None,
+    };
+    let text = serde_json::to_string(&request).unwrap();
+    assert_eq!(Request::from_json(&text).unwrap(), request);
+    let expected = Response {
+        power: Some(0.773),
+        ncp: Some(12.5),
+        df: vec![4.0, 45.0],
+        critical_value: Some(2.579),
+        ..Default::default()
+    };
+    assert_eq!(request.calculate().unwrap(), expected);
+    let expected = json!({"power": 0.773, "ncp": 12.5, "df": [4.0, 45.0], "criticalValue": 2.579});
+    assert_eq!(json!(request.calculate().unwrap()), expected);
+}
+
+#[test]
+fn calculate_power_with_large_payload() {
+    let join = with_rest("oneWayANOVA");
+    let extra = json!({"k": "5", "analysis": "power"});
+    let text = join(&extra).to_string() + &" ".repeat(4096);
+    let returned = unsafe {
+        let ptr = calculatePower(text.as_ptr(), text.len());
+        let prefix = std::slice::from_raw_parts(ptr, LENGTH_PREFIX);
+        let length = u32::from_le_bytes(prefix.try_into().unwrap()) as usize;
+        let returned = read_from_ptr(ptr.add(LENGTH_PREFIX), length).unwrap();
+        dealloc(ptr, LENGTH_PREFIX + length);
+        returned
+    };
+    let returned: Value = serde_json::from_str(&returned).unwrap();
+    assert_eq!(returned["power"], 0.773);
+}
+
+#[test]
+fn small_effect_sizes() {
+    let join = with_rest("oneSampleTTest");
+    let extra = json!({"tail": "2", "analysis": "n", "es": 0.05});
+    test_interface(&join(&extra), 5200.0);
+    let extra = json!({"tail": "2", "analysis": "n", "es": 0.0});
+    test_error(&join(&extra), "no_solution", None);
+}
+
+#[test]
+fn n_matches_pwr() {
+    // pwr.t.test(d=0.2, power=0.8, sig.level=0.05, type="one.sample") gives n = 198.15.
+    let join = with_rest("oneSampleTTest");
+    let extra = json!({"tail": "2", "analysis": "n", "es": 0.2, "power": 0.8});
+    test_interface(&join(&extra), 199.0);
+    // pwr.anova.test(k=4, f=0.25, sig.level=0.05, power=0.8) gives n = 44.6 per group.
+    let join = with_rest("oneWayANOVA");
+    let extra = json!({"k": "4", "analysis": "n", "es": 0.25, "power": 0.8});
+    test_interface(&join(&extra), 179.0);
+}
+
+#[test]
+fn n_is_smallest_integer_reaching_power() {
+    let tests = [
+        TestKind::OneSampleTTest,
+        TestKind::GoodnessOfFitChisqTest { df: 5 },
+        TestKind::ANCOVA {
+            k: 10,
+            q: 50,
+            p: 10,
+        },
+        TestKind::BetweenRepeatedANOVA {
+            k: 5,
+            m: 10,
+            rho: 0.75,
+        },
+    ];
+    for test in tests {
+        // Repeated measures designs grow by a subject per group.
+        let step = match test {
+            TestKind::BetweenRepeatedANOVA { k, .. } => k as f64,
+            _ => 1.0,
+        };
+        for tail in [Tail::OneSided, Tail::TwoSided] {
+            let n = test.n(tail.clone(), ALPHA, POWER, ES).unwrap().1 as f64;
+            assert!(test.power(tail.clone(), n, ALPHA, ES).unwrap() >= POWER);
+            assert!(test.power(tail.clone(), n - step, ALPHA, ES).unwrap() < POWER);
+        }
+    }
+    // For two groups, the size of group 1 is the smallest one.
+    let test = TestKind::IndependentSamplesTTest {
+        allocation: Allocation {
+            alloc_ratio: 1.5,
+            ..Default::default()
+        },
+    };
+    let (design, n) = test.n(Tail::TwoSided, ALPHA, POWER, ES).unwrap();
+    let (n1, n2) = match design.allocation() {
+        Some(Allocation {
+            n1: Some(n1),
+            n2: Some(n2),
+            ..
+        }) => (*n1, *n2),
+        _ => panic!("the group sizes are not set"),
+    };
+    assert_eq!(n2, (1.5 * n1).ceil());
+    assert_eq!(n1 + n2, n as f64);
+    let power = |n1: f64, n2: f64| {
+        let test = test.with_group_sizes(n1, n2);
+        test.power(Tail::TwoSided, n1 + n2, ALPHA, ES).unwrap()
+    };
+    assert!(power(n1, n2) >= POWER);
+    assert!(power(n1 - 1.0, (1.5 * (n1 - 1.0)).ceil()) < POWER);
+}
+
+#[test]
+fn tests_built_in_code_are_validated() {
+    // Missing parameters give the same errors as in a request instead of a panic.
+    let field = |result: Result<f64, PowerError>| result.unwrap_err().field.unwrap();
+    let test = TestKind::IndependentSamplesWelchTTest {
+        allocation: Allocation::default(),
+        sd1: None,
+        sd2: None,
+        var_ratio: None,
+    };
+    assert_eq!(field(test.power(Tail::TwoSided, N, ALPHA, ES)), "varRatio");
+    let test = TestKind::TwoProportionsTest {
+        allocation: Allocation::default(),
+        p1: None,
+        p2: None,
+        method: Default::default(),
+    };
+    let n = test
+        .n(Tail::TwoSided, ALPHA, POWER, ES)
+        .map(|(_, n)| n as f64);
+    assert_eq!(field(n), "p1");
+    let test = TestKind::McNemarTest {
+        prop_discordant: None,
+        p12: None,
+        p21: None,
+        method: Default::default(),
+    };
+    assert_eq!(
+        field(test.es(Tail::TwoSided, Direction::Greater, N, ALPHA, POWER)),
+        "propDiscordant"
+    );
+    let test = TestKind::SurvivalTest {
+        allocation: Allocation::default(),
+        median1: None,
+        accrual: None,
+        follow_up: None,
+        event_prob1: None,
+        event_prob2: None,
+        method: Default::default(),
+    };
+    assert_eq!(field(test.alpha(Tail::TwoSided, N, POWER, 1.5)), "median1");
+    let details = test.details(Tail::TwoSided, N, ALPHA, 1.5);
+    assert_eq!(details.unwrap_err().field.unwrap(), "median1");
+}
+
+#[test]
+fn a_priori_details() {
+    // G*Power: t tests - Means: Difference from constant (one sample case).
+    let join = with_rest("oneSampleTTest");
+    let extra = json!({"tail": "2", "analysis": "n"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(returned["n"], 54);
+    assert_eq!(returned["actualPower"], 0.95); // G*Power gives 0.9502
+    assert_eq!(returned["ncp"], 3.674);
+    assert_eq!(returned["df"], json!([53.0]));
+    assert_eq!(returned["criticalValue"], 2.006); // G*Power gives 2.0057
+
+    // G*Power: F tests - ANOVA: Fixed effects, omnibus, one-way.
+    let join = with_rest("oneWayANOVA");
+    let extra = json!({"k": "5", "analysis": "n"});
+    let returned = handle_received(&join(&extra).to_string());
+    assert_eq!(returned["n"], 80);
+    assert_eq!(returned["actualPower"], 0.952);
+    assert_eq!(returned["ncp"], 20.0);
+    assert_eq!(returned["df"], json!([4.0, 75.0]));
+    assert_eq!(returned["criticalValue"], 2.494); // G*Power gives 2.4937
 }